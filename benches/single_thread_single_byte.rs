@@ -33,7 +33,7 @@ pub fn criterion_benchmark(criterion: &mut criterion::Criterion) {
         v.pop().unwrap()
     });
 
-    let (mut p, mut c) = RingBuffer::<u8>::new(1);
+    let (mut p, mut c) = RingBuffer::<u8>::new(1, 0);
 
     add_function(&mut group, "1-push-pop", |i| {
         p.push(i).unwrap();
@@ -34,7 +34,7 @@ pub fn criterion_benchmark(criterion: &mut criterion::Criterion) {
     group.throughput(criterion::Throughput::Bytes(CHUNK_SIZE as u64));
     group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
 
-    let (mut p, mut c) = RingBuffer::<u8>::new(CHUNK_SIZE + 1);
+    let (mut p, mut c) = RingBuffer::<u8>::new(CHUNK_SIZE + 1, 0);
 
     add_function(&mut group, "2-slice-read", |data| {
         let mut result = [0; CHUNK_SIZE];
@@ -1,3 +1,6 @@
+// The MSRV only applies to the library itself, not to the benchmarks.
+#![allow(clippy::incompatible_msrv)]
+
 macro_rules! create_two_threads_benchmark {
     ($($id:literal, $create:expr, $push:expr, $pop:expr);+) => {
 
@@ -165,7 +168,7 @@ criterion_main!(benches);
 
 create_two_threads_benchmark!(
     "rtrb",
-    rtrb::RingBuffer::new,
+    |capacity| rtrb::RingBuffer::new(capacity, 0),
     |p, i| p.push(i).is_ok(),
     |c| c.pop().ok()
 );
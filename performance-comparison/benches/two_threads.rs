@@ -19,7 +19,7 @@ create_two_threads_benchmark!(
     |q| q.pop().ok();
 
     "3-rtrb",
-    rtrb::RingBuffer::new,
+    |capacity| rtrb::RingBuffer::new(capacity, 0),
     |p, i| p.push(i).is_ok(),
     |c| c.pop().ok();
 
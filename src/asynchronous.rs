@@ -0,0 +1,325 @@
+//! Implementations of [`Stream`] for [`Consumer`] and [`Sink`] for [`Producer`].
+//!
+//! With the `tokio` feature, [`Producer<u8>`] implements `AsyncWrite`
+//! and [`Consumer<u8>`] implements `AsyncRead` and `AsyncBufRead`.
+//!
+//! Each side has its own [`AtomicWaker`](atomic_waker::AtomicWaker),
+//! which is woken by the other side after each write or read
+//! (including acknowledgements and changes of the resend window).
+//! The side that is not polled as a future doesn't have to run in an async context,
+//! e.g. a realtime thread can push items into a ring buffer that is consumed as a stream.
+//!
+//! This module is only available with the `async` feature
+//! (which is enabled by the `tokio` feature).
+
+use core::pin::Pin;
+use core::sync::atomic::{fence, Ordering};
+use core::task::{Context, Poll};
+
+use futures_core::Stream;
+use futures_sink::Sink;
+
+use crate::{Consumer, OverflowPolicy, PopError, Producer, PushError, PINNED};
+
+/// Items are popped as they become available, [`PopError::Lagged`] is skipped.
+///
+/// The stream ends when the [`Producer`] has been dropped and all items have been consumed.
+///
+/// Only available with the `async` feature.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use futures::StreamExt;
+/// use rtrb::RingBuffer;
+///
+/// let (mut p, c) = RingBuffer::new(4, 0);
+///
+/// let t = std::thread::spawn(move || {
+///     for i in 0..10 {
+///         while p.push(i).is_err() {
+///             std::thread::yield_now();
+///         }
+///     }
+/// });
+/// let items: Vec<_> = block_on(c.collect());
+/// assert_eq!(items, (0..10).collect::<Vec<_>>());
+/// t.join().unwrap();
+/// ```
+///
+/// The number of overwritten items can be obtained with [`Consumer::lost_total()`].
+impl<T> Stream for Consumer<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        loop {
+            if !this.poll_readable(cx) {
+                return Poll::Pending;
+            }
+            match this.pop() {
+                Ok(value) => return Poll::Ready(Some(value)),
+                Err(PopError::Lagged { .. }) => {}
+                // The producer has been dropped.
+                Err(PopError::Empty) => return Poll::Ready(None),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.slots(), None)
+    }
+}
+
+/// Items are pushed as soon as a slot is available.
+///
+/// Slots that are protected by the resend window are not available,
+/// the task is woken when they are released (by consuming or acknowledging items).
+///
+/// With [`OverflowPolicy::OverwriteOldest`], the oldest item is overwritten
+/// instead of waiting (unless the [`Consumer`] is currently reading it).
+/// This happens in `start_send()`, `poll_ready()` doesn't modify the queue.
+/// If the [`Consumer`] has started reading the oldest item in the meantime,
+/// `start_send()` returns [`PushError::Full`].
+/// With [`OverflowPolicy::DropNewest`], the sink never waits
+/// and items that don't fit into the queue are dropped without an error
+/// (they are counted in [`RingBuffer::overflowed()`](crate::RingBuffer::overflowed)).
+///
+/// Items are visible to the [`Consumer`] immediately, flushing and closing don't wait.
+/// If the [`Consumer`] has been dropped, the sink stops waiting and
+/// [`PushError::Full`] is returned once the queue is full.
+///
+/// Note that with [`SinkExt`](https://docs.rs/futures/0.3/futures/sink/trait.SinkExt.html)
+/// in scope, `p.buffer()` refers to `SinkExt::buffer()`,
+/// [`Producer::buffer()`] can still be called as `Producer::buffer(&p)`.
+///
+/// Only available with the `async` feature.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use futures::SinkExt;
+/// use rtrb::RingBuffer;
+///
+/// let (mut p, mut c) = RingBuffer::new(4, 0);
+///
+/// let t = std::thread::spawn(move || {
+///     block_on(async {
+///         for i in 0..10 {
+///             p.send(i).await.unwrap();
+///         }
+///     })
+/// });
+/// for i in 0..10 {
+///     loop {
+///         if let Ok(value) = c.pop() {
+///             assert_eq!(value, i);
+///             break;
+///         }
+///         std::thread::yield_now();
+///     }
+/// }
+/// t.join().unwrap();
+/// ```
+impl<T> Sink<T> for Producer<T> {
+    type Error = PushError<T>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.can_push() {
+            return Poll::Ready(Ok(()));
+        }
+        // The consumer might have made space before the waker was registered,
+        // therefore it is checked once more afterwards.
+        this.buffer.producer_waker.register(cx.waker());
+        if this.can_push() {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        match self.get_mut().push(item) {
+            Ok(_) | Err(PushError::Dropped) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> Producer<T> {
+    /// Returns `true` if the next call to `push()` is not going to wait for the consumer.
+    ///
+    /// This doesn't modify the queue,
+    /// with [`OverflowPolicy::OverwriteOldest`] the oldest item is only overwritten by `push()`.
+    fn can_push(&self) -> bool {
+        if self.slots() > 0 || self.is_abandoned() {
+            return true;
+        }
+        match self.buffer.overflow_policy {
+            OverflowPolicy::Block => false,
+            // The oldest item cannot be overwritten while the consumer is accessing it.
+            OverflowPolicy::OverwriteOldest => {
+                self.buffer.capacity > 0 && self.buffer.head.load(Ordering::Relaxed) & PINNED == 0
+            }
+            OverflowPolicy::DropNewest => true,
+        }
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Returns `true` if items are available or if the [`Producer`] has been dropped.
+    ///
+    /// Otherwise, the task is registered to be woken when items become available.
+    fn poll_readable(&self, cx: &mut Context<'_>) -> bool {
+        let readable = || {
+            if self.is_abandoned() {
+                // Make sure that the last items written by the producer are visible.
+                fence(Ordering::Acquire);
+                return true;
+            }
+            !self.is_empty()
+        };
+        if readable() {
+            return true;
+        }
+        // The producer might have pushed items before the waker was registered,
+        // therefore it is checked once more afterwards.
+        self.buffer.consumer_waker.register(cx.waker());
+        readable()
+    }
+}
+
+/// Writes as many bytes as there are slots available, see [`std::io::Write`].
+///
+/// The task is woken when the [`Consumer`] has made space.
+/// If the [`Consumer`] has been dropped and the queue is full,
+/// an error of kind [`BrokenPipe`](std::io::ErrorKind::BrokenPipe) is returned.
+/// Flushing and shutting down don't wait, the [`Consumer`] only reaches
+/// the end of the stream once the [`Producer`] is dropped.
+///
+/// Only available with the `tokio` feature.
+#[cfg(feature = "tokio")]
+impl tokio_crate::io::AsyncWrite for Producer<u8> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        use std::io::{ErrorKind, Write};
+
+        let this = self.get_mut();
+        match this.write(buf) {
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            result => return Poll::Ready(result),
+        }
+        if this.is_abandoned() {
+            return Poll::Ready(Err(ErrorKind::BrokenPipe.into()));
+        }
+        // The consumer might have made space before the waker was registered,
+        // therefore it is checked once more afterwards.
+        this.buffer.producer_waker.register(cx.waker());
+        match this.write(buf) {
+            Err(e) if e.kind() == ErrorKind::WouldBlock => Poll::Pending,
+            result => Poll::Ready(result),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Reads as many bytes as are available, see [`std::io::Read`].
+///
+/// The task is woken whenever the [`Producer`] has written bytes.
+/// The end of the stream is reached when the [`Producer`] has been dropped
+/// and all bytes have been consumed.
+///
+/// Only available with the `tokio` feature.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use rtrb::RingBuffer;
+/// use tokio_crate::io::AsyncReadExt;
+///
+/// let (mut p, mut c) = RingBuffer::new(4, 0);
+///
+/// let t = std::thread::spawn(move || {
+///     for i in 0..10 {
+///         while p.push(i).is_err() {
+///             std::thread::yield_now();
+///         }
+///     }
+/// });
+/// let mut data = Vec::new();
+/// assert_eq!(block_on(c.read_to_end(&mut data)).unwrap(), 10);
+/// assert_eq!(data, (0..10).collect::<Vec<_>>());
+/// t.join().unwrap();
+/// ```
+#[cfg(feature = "tokio")]
+impl tokio_crate::io::AsyncRead for Consumer<u8> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio_crate::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        use std::io::{ErrorKind, Read};
+
+        let this = self.get_mut();
+        if !this.poll_readable(cx) {
+            return Poll::Pending;
+        }
+        match this.read(buf.initialize_unfilled()) {
+            Ok(n) => {
+                buf.advance(n);
+                Poll::Ready(Ok(()))
+            }
+            // The producer has been dropped.
+            Err(e) if e.kind() == ErrorKind::WouldBlock => Poll::Ready(Ok(())),
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
+/// Provides the first contiguous part of the available bytes without copying.
+///
+/// The ring buffer wraps around, so the returned slice might contain only a part
+/// of the available bytes, the rest is returned after consuming it.
+/// Consumed bytes are committed like in [`std::io::BufRead`] for [`Consumer<u8>`].
+///
+/// Only available with the `tokio` feature.
+#[cfg(feature = "tokio")]
+impl tokio_crate::io::AsyncBufRead for Consumer<u8> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if !this.poll_readable(cx) {
+            return Poll::Pending;
+        }
+        match std::io::BufRead::fill_buf(this) {
+            // The producer has been dropped.
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Poll::Ready(Ok(&[])),
+            result => Poll::Ready(result),
+        }
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        std::io::BufRead::consume(self.get_mut(), amt);
+    }
+}
@@ -0,0 +1,552 @@
+//! Blocking variants of [`Producer::push()`], [`Consumer::pop()`] and [`Consumer::read_chunk()`].
+//!
+//! Only the non-realtime side ever waits,
+//! the other side only checks a single atomic "parking word" after each write or read
+//! and wakes the waiting thread (with [`Thread::unpark()`]) if necessary.
+//! Parking needs a memory fence on the notifying side, therefore it has to be enabled
+//! with [`enable_parking()`] before the [`Producer`] and the [`Consumer`] are used.
+//! Otherwise, the check is a single relaxed load.
+//!
+//! How a thread waits can be chosen separately for the [`Producer`] and the [`Consumer`] with
+//! [`Producer::set_wait_strategy()`] and [`Consumer::set_wait_strategy()`],
+//! using one of the provided [`WaitStrategy`] implementations or a custom one.
+//! By default, the thread is parked ([`Park`]).
+//! All other strategies work without [`enable_parking()`].
+//!
+//! This module is only available with the `std` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use rtrb::blocking::{ExponentialBackoff, Yield};
+//! use rtrb::RingBuffer;
+//! use std::time::Duration;
+//!
+//! let (mut p, mut c) = RingBuffer::new(2, 0);
+//! p.set_wait_strategy(Yield);
+//! c.set_wait_strategy(ExponentialBackoff::default());
+//!
+//! let t = std::thread::spawn(move || {
+//!     for i in 0..10 {
+//!         p.push_blocking(i, Duration::from_secs(10)).unwrap();
+//!     }
+//! });
+//! for i in 0..10 {
+//!     assert_eq!(c.pop_blocking(Duration::from_secs(10)), Ok(i));
+//! }
+//! t.join().unwrap();
+//! ```
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use crate::chunks::{ChunkError, ReadChunk};
+use crate::{Consumer, PopError, Producer, PushError};
+
+/// Nobody is waiting.
+const IDLE: usize = 0;
+
+/// The waiting thread is being woken up.
+const NOTIFYING: usize = !0;
+
+/// Decides how a thread waits for the other side of the ring buffer.
+///
+/// The condition is checked again after each [`WaitStep`],
+/// until it is fulfilled or the timeout has passed.
+pub trait WaitStrategy: fmt::Debug + Send {
+    /// Returns how to wait after the condition has been checked `iteration + 1` times.
+    ///
+    /// `iteration` starts at `0` for each call to a blocking method.
+    fn step(&mut self, iteration: u32) -> WaitStep;
+}
+
+/// A single step of a [`WaitStrategy`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WaitStep {
+    /// Busy-wait, hinting the CPU that this is a spin loop.
+    Spin,
+    /// Yield the time slice of the thread to the OS scheduler.
+    Yield,
+    /// Sleep for the given duration (but not past the timeout).
+    Sleep(Duration),
+    /// Park the thread until the other side makes progress (or the timeout has passed).
+    Park,
+}
+
+/// Waits in a busy loop, for the lowest latency at the cost of a fully used CPU core.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct BusySpin;
+
+impl WaitStrategy for BusySpin {
+    fn step(&mut self, _iteration: u32) -> WaitStep {
+        WaitStep::Spin
+    }
+}
+
+/// Yields to other threads between checks.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Yield;
+
+impl WaitStrategy for Yield {
+    fn step(&mut self, _iteration: u32) -> WaitStep {
+        WaitStep::Yield
+    }
+}
+
+/// Spins first, then yields and finally sleeps with exponentially growing durations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExponentialBackoff {
+    /// The number of [`WaitStep::Spin`] steps.
+    pub spins: u32,
+    /// The number of [`WaitStep::Yield`] steps after spinning.
+    pub yields: u32,
+    /// The duration of the first [`WaitStep::Sleep`], which is doubled for each further step.
+    pub min_sleep: Duration,
+    /// The upper limit for the sleep duration.
+    pub max_sleep: Duration,
+}
+
+impl Default for ExponentialBackoff {
+    /// Spins 64 times, yields 16 times and sleeps between 10 µs and 10 ms.
+    fn default() -> Self {
+        ExponentialBackoff {
+            spins: 64,
+            yields: 16,
+            min_sleep: Duration::from_micros(10),
+            max_sleep: Duration::from_millis(10),
+        }
+    }
+}
+
+impl WaitStrategy for ExponentialBackoff {
+    fn step(&mut self, iteration: u32) -> WaitStep {
+        if iteration < self.spins {
+            return WaitStep::Spin;
+        }
+        let iteration = iteration - self.spins;
+        if iteration < self.yields {
+            return WaitStep::Yield;
+        }
+        let exponent = (iteration - self.yields).min(31);
+        let sleep = self
+            .min_sleep
+            .checked_mul(1 << exponent)
+            .unwrap_or(self.max_sleep);
+        WaitStep::Sleep(sleep.min(self.max_sleep))
+    }
+}
+
+/// Parks the thread until it is woken by the other side, without using any CPU time.
+///
+/// This is the default strategy, it requires [`enable_parking()`].
+/// Waking the thread requires a system call on the other side, but only once per wait.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Park;
+
+impl WaitStrategy for Park {
+    fn step(&mut self, _iteration: u32) -> WaitStep {
+        WaitStep::Park
+    }
+}
+
+/// A parking word for one side of the ring buffer.
+///
+/// While a thread is waiting, the state contains the number of items (or slots)
+/// it is waiting for.
+pub(crate) struct Waiter {
+    state: AtomicUsize,
+    /// Whether parking has been enabled (never goes back).
+    ///
+    /// This is only set while nobody can notify, see [`enable_parking()`].
+    /// Without parking, notifying doesn't cost more than a relaxed load.
+    parking: AtomicBool,
+    /// Only accessed by the waiting thread while the state is `IDLE`
+    /// and by the notifying thread while the state is `NOTIFYING`.
+    thread: UnsafeCell<Option<Thread>>,
+    /// Only accessed by the side of the ring buffer that waits.
+    strategy: UnsafeCell<Box<dyn WaitStrategy>>,
+}
+
+// SAFETY: Access to `thread` is synchronized by `state`, see above.
+// `strategy` is only accessed by a single thread at a time.
+unsafe impl Sync for Waiter {}
+
+impl fmt::Debug for Waiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Waiter")
+            .field("state", &self.state.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl Waiter {
+    pub(crate) fn new() -> Self {
+        Waiter {
+            state: AtomicUsize::new(IDLE),
+            parking: AtomicBool::new(false),
+            thread: UnsafeCell::new(None),
+            strategy: UnsafeCell::new(Box::new(Park)),
+        }
+    }
+
+    /// Makes `notify()` use a fence, which is needed for parking.
+    ///
+    /// This must only be called while nobody can call `notify()`.
+    fn enable_parking(&self) {
+        self.parking.store(true, Ordering::Relaxed);
+    }
+
+    /// Replaces the wait strategy.
+    ///
+    /// # Safety
+    ///
+    /// This must only be called by the side of the ring buffer that waits on `self`.
+    unsafe fn set_strategy(&self, strategy: Box<dyn WaitStrategy>) {
+        // SAFETY: The caller is the only thread accessing the strategy.
+        unsafe { *self.strategy.get() = strategy };
+    }
+
+    /// Wakes the waiting thread (if any) if `available()` reaches its threshold.
+    ///
+    /// This is wait-free, `available()` is only called if a thread is waiting.
+    /// It has to be called after the new position has been stored.
+    pub(crate) fn notify(&self, available: impl FnOnce() -> usize) {
+        if !self.parking.load(Ordering::Relaxed) {
+            return;
+        }
+        // This pairs with the fence in park().
+        fence(Ordering::SeqCst);
+        let state = self.state.load(Ordering::Relaxed);
+        if state == IDLE || state == NOTIFYING || available() < state {
+            return;
+        }
+        if self
+            .state
+            .compare_exchange(state, NOTIFYING, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            // SAFETY: The waiting thread doesn't access this while the state is NOTIFYING.
+            if let Some(thread) = unsafe { &*self.thread.get() } {
+                thread.unpark();
+            }
+            self.state.store(IDLE, Ordering::Release);
+        }
+    }
+
+    /// Blocks until `ready()` returns `true` or until the deadline has passed.
+    ///
+    /// The other side has to call `notify()` whenever `ready()` might have become `true`.
+    /// `threshold` is passed on to `notify()`.
+    /// Returns `false` on timeout.
+    ///
+    /// # Safety
+    ///
+    /// This must only be called by the side of the ring buffer that waits on `self`.
+    unsafe fn wait(
+        &self,
+        threshold: usize,
+        deadline: Option<Instant>,
+        mut ready: impl FnMut() -> bool,
+    ) -> bool {
+        // SAFETY: The caller is the only thread accessing the strategy.
+        let strategy = unsafe { &mut *self.strategy.get() };
+        let mut iteration = 0;
+        loop {
+            if ready() {
+                return true;
+            }
+            let remaining = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return false;
+                    }
+                    Some(deadline - now)
+                }
+                None => None,
+            };
+            match strategy.step(iteration) {
+                WaitStep::Spin => {
+                    #[allow(deprecated)]
+                    core::sync::atomic::spin_loop_hint();
+                }
+                WaitStep::Yield => thread::yield_now(),
+                WaitStep::Sleep(duration) => {
+                    thread::sleep(remaining.map_or(duration, |r| r.min(duration)))
+                }
+                WaitStep::Park => {
+                    if self.park(threshold, remaining, &mut ready) {
+                        return true;
+                    }
+                }
+            }
+            iteration = iteration.saturating_add(1);
+        }
+    }
+
+    /// Parks the current thread until it is notified (or the timeout has passed).
+    ///
+    /// Returns `true` if `ready()` returned `true` after registering the thread.
+    fn park(
+        &self,
+        threshold: usize,
+        timeout: Option<Duration>,
+        ready: &mut impl FnMut() -> bool,
+    ) -> bool {
+        assert!(
+            self.parking.load(Ordering::Relaxed),
+            "Parking has not been enabled, see rtrb::blocking::enable_parking()"
+        );
+        // SAFETY: The state is IDLE, the notifying thread doesn't access this.
+        let thread = unsafe { &mut *self.thread.get() };
+        let current = thread::current();
+        if thread.as_ref().map(Thread::id) != Some(current.id()) {
+            *thread = Some(current);
+        }
+        let threshold = threshold.max(1).min(NOTIFYING - 1);
+        // This makes `thread` visible to the compare_exchange() in notify().
+        self.state.store(threshold, Ordering::Release);
+        // This pairs with the fence in notify().
+        fence(Ordering::SeqCst);
+        let result = ready();
+        if !result {
+            match timeout {
+                Some(timeout) => thread::park_timeout(timeout),
+                None => thread::park(),
+            }
+        }
+        self.cancel(threshold);
+        result
+    }
+
+    /// Stops waiting, the state is `IDLE` afterwards.
+    fn cancel(&self, threshold: usize) {
+        if self
+            .state
+            .compare_exchange(threshold, IDLE, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            // The notifying thread is about to finish.
+            while self.state.load(Ordering::Acquire) != IDLE {
+                thread::yield_now();
+            }
+        }
+    }
+}
+
+/// Allows the [`Producer`] and the [`Consumer`] to park while waiting ([`Park`]).
+///
+/// From now on, both sides use a memory fence when notifying the other side.
+/// Taking both of them ensures that no notification can be missed
+/// (because neither of them can be in use on another thread).
+///
+/// # Panics
+///
+/// Panics if `producer` and `consumer` don't belong to the same ring buffer.
+///
+/// # Examples
+///
+/// ```
+/// use rtrb::RingBuffer;
+/// use std::time::Duration;
+///
+/// let (mut p, mut c) = RingBuffer::new(2, 0);
+/// rtrb::blocking::enable_parking(&mut p, &mut c);
+/// let t = std::thread::spawn(move || {
+///     std::thread::sleep(Duration::from_millis(10));
+///     p.push(10).unwrap();
+/// });
+/// assert_eq!(c.pop_blocking(Duration::from_secs(10)), Ok(10));
+/// t.join().unwrap();
+/// ```
+pub fn enable_parking<T>(producer: &mut Producer<T>, consumer: &mut Consumer<T>) {
+    assert!(
+        Arc::ptr_eq(&producer.buffer, &consumer.buffer),
+        "Producer and Consumer belong to different ring buffers"
+    );
+    producer.buffer.producer_waiter.enable_parking();
+    producer.buffer.consumer_waiter.enable_parking();
+}
+
+/// Returns `None` if the timeout is too large to be represented.
+fn deadline(timeout: Duration) -> Option<Instant> {
+    Instant::now().checked_add(timeout)
+}
+
+impl<T> Producer<T> {
+    /// Chooses how [`Producer::push_blocking()`] waits.
+    ///
+    /// The default is [`Park`], which requires [`enable_parking()`].
+    ///
+    /// Only available with the `std` feature.
+    pub fn set_wait_strategy(&mut self, strategy: impl WaitStrategy + 'static) {
+        // SAFETY: Only the producer waits on this.
+        unsafe { self.buffer.producer_waiter.set_strategy(Box::new(strategy)) };
+    }
+
+    /// Pushes an element into the queue, waiting for a free slot if necessary.
+    ///
+    /// This blocks the calling thread (see [`Producer::set_wait_strategy()`]),
+    /// it must not be used on a realtime thread.
+    /// The [`Consumer`] doesn't block, it only wakes this thread when it has made progress.
+    /// This includes releasing retained items from the resend window
+    /// (e.g. with [`Consumer::ack()`]).
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// # Errors
+    ///
+    /// If no slot becomes available within `timeout`
+    /// (or if the [`Consumer`] has been dropped), the element is returned back
+    /// like in [`Producer::push()`].
+    /// [`PushError::Dropped`] is returned immediately,
+    /// see [`OverflowPolicy::DropNewest`](crate::OverflowPolicy::DropNewest).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread is about to park but [`enable_parking()`] has not been called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{PushError, RingBuffer};
+    /// use std::time::Duration;
+    ///
+    /// let (mut p, mut c) = RingBuffer::new(1, 0);
+    /// rtrb::blocking::enable_parking(&mut p, &mut c);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// let t = std::thread::spawn(move || p.push_blocking(20, Duration::from_secs(10)));
+    /// assert_eq!(c.pop_blocking(Duration::from_secs(10)), Ok(10));
+    /// assert_eq!(t.join().unwrap(), Ok(1));
+    /// assert_eq!(c.pop_blocking(Duration::from_millis(1)), Ok(20));
+    /// ```
+    pub fn push_blocking(&mut self, value: T, timeout: Duration) -> Result<u64, PushError<T>> {
+        let deadline = deadline(timeout);
+        let mut value = value;
+        loop {
+            let full = match self.push(value) {
+                Err(PushError::Full(v)) if !self.is_abandoned() => PushError::Full(v),
+                Err(PushError::WindowFull(v)) if !self.is_abandoned() => PushError::WindowFull(v),
+                result => return result,
+            };
+            let waiter = &self.buffer.producer_waiter;
+            // SAFETY: Only the producer waits on this.
+            let ready =
+                unsafe { waiter.wait(1, deadline, || self.slots() > 0 || self.is_abandoned()) };
+            if !ready {
+                return Err(full);
+            }
+            value = match full {
+                PushError::Full(v) | PushError::WindowFull(v) => v,
+                PushError::Dropped => unreachable!(),
+            };
+        }
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Chooses how [`Consumer::pop_blocking()`] and [`Consumer::read_chunk_blocking()`] wait.
+    ///
+    /// The default is [`Park`], which requires [`enable_parking()`].
+    ///
+    /// Only available with the `std` feature.
+    pub fn set_wait_strategy(&mut self, strategy: impl WaitStrategy + 'static) {
+        // SAFETY: Only the consumer waits on this.
+        unsafe { self.buffer.consumer_waiter.set_strategy(Box::new(strategy)) };
+    }
+
+    /// Pops an element from the queue, waiting for one to become available if necessary.
+    ///
+    /// This blocks the calling thread (see [`Consumer::set_wait_strategy()`]),
+    /// it must not be used on a realtime thread.
+    /// The [`Producer`] doesn't block, it only wakes this thread when it has pushed items.
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// # Errors
+    ///
+    /// If no element becomes available within `timeout`
+    /// (or if the [`Producer`] has been dropped), [`PopError::Empty`] is returned.
+    /// [`PopError::Lagged`] is returned like in [`Consumer::pop()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread is about to park but [`enable_parking()`] has not been called.
+    pub fn pop_blocking(&mut self, timeout: Duration) -> Result<T, PopError> {
+        let deadline = deadline(timeout);
+        loop {
+            match self.pop() {
+                Err(PopError::Empty) if !self.is_abandoned() => {}
+                result => return result,
+            }
+            let buffer = &self.buffer;
+            // SAFETY: Only the consumer waits on this.
+            let ready = unsafe {
+                buffer
+                    .consumer_waiter
+                    .wait(1, deadline, || buffer.readable() > 0 || self.is_abandoned())
+            };
+            if !ready {
+                return Err(PopError::Empty);
+            }
+        }
+    }
+
+    /// Waits until `n` slots are available for reading and returns them as [`ReadChunk`].
+    ///
+    /// The waiting thread is only woken once at least `n` items have been pushed
+    /// (or if the [`Producer`] has been dropped).
+    /// This blocks the calling thread (see [`Consumer::set_wait_strategy()`]),
+    /// it must not be used on a realtime thread.
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// # Errors
+    ///
+    /// If not enough slots become available within `timeout`,
+    /// the error from [`Consumer::read_chunk()`] is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread is about to park but [`enable_parking()`] has not been called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    /// use std::time::Duration;
+    ///
+    /// let (mut p, mut c) = RingBuffer::new(4, 0);
+    /// rtrb::blocking::enable_parking(&mut p, &mut c);
+    /// let t = std::thread::spawn(move || {
+    ///     for i in 0..3 {
+    ///         p.push_blocking(i, Duration::from_secs(10)).unwrap();
+    ///     }
+    /// });
+    /// let chunk = c.read_chunk_blocking(3, Duration::from_secs(10)).unwrap();
+    /// assert_eq!(chunk.as_slices(), (&[0, 1, 2][..], &[][..]));
+    /// chunk.commit_all();
+    /// t.join().unwrap();
+    /// ```
+    pub fn read_chunk_blocking(
+        &mut self,
+        n: usize,
+        timeout: Duration,
+    ) -> Result<ReadChunk<'_, T>, ChunkError> {
+        let buffer = &self.buffer;
+        if n <= buffer.capacity {
+            // SAFETY: Only the consumer waits on this.
+            unsafe {
+                buffer.consumer_waiter.wait(n, deadline(timeout), || {
+                    buffer.readable() >= n || self.is_abandoned()
+                })
+            };
+        }
+        self.read_chunk(n)
+    }
+}
@@ -21,7 +21,7 @@
 //! ```
 //! use rtrb::RingBuffer;
 //!
-//! let (mut producer, mut consumer) = RingBuffer::new(5);
+//! let (mut producer, mut consumer) = RingBuffer::new(5, 0);
 //!
 //! if let Ok(chunk) = producer.write_chunk_uninit(4) {
 //!     chunk.fill_from_iter([10, 11, 12]);
@@ -117,7 +117,7 @@
 //! Copy as many items as possible from a given slice, returning the number of copied items:
 //!
 //! ```
-//! use rtrb::{Producer, CopyToUninit, chunks::ChunkError::TooFewSlots};
+//! use rtrb::{Producer, CopyToUninit, chunks::ChunkError::{Lagged, ResendWindowFull, TooFewSlots}};
 //!
 //! fn push_partial_slice<T>(queue: &mut Producer<T>, slice: &[T]) -> usize
 //! where
@@ -126,7 +126,11 @@
 //!     let mut chunk = match queue.write_chunk_uninit(slice.len()) {
 //!         Ok(chunk) => chunk,
 //!         // Remaining slots are returned, this will always succeed:
-//!         Err(TooFewSlots(n)) => queue.write_chunk_uninit(n).unwrap(),
+//!         Err(TooFewSlots(n)) | Err(ResendWindowFull { usable: n, .. }) => {
+//!             queue.write_chunk_uninit(n).unwrap()
+//!         }
+//!         // Overwritten items are only reported when reading:
+//!         Err(Lagged { .. }) => unreachable!(),
 //!     };
 //!     let end = chunk.len();
 //!     let (first, second) = chunk.as_mut_slices();
@@ -143,7 +147,7 @@
 //! (and return the number of written slots):
 //!
 //! ```
-//! use rtrb::{Producer, chunks::ChunkError::TooFewSlots};
+//! use rtrb::{Producer, chunks::ChunkError::{Lagged, ResendWindowFull, TooFewSlots}};
 //!
 //! fn push_from_iter<T, I>(queue: &mut Producer<T>, iter: I) -> usize
 //! where
@@ -158,7 +162,11 @@
 //!     let chunk = match queue.write_chunk_uninit(n) {
 //!         Ok(chunk) => chunk,
 //!         // Remaining slots are returned, this will always succeed:
-//!         Err(TooFewSlots(n)) => queue.write_chunk_uninit(n).unwrap(),
+//!         Err(TooFewSlots(n)) | Err(ResendWindowFull { usable: n, .. }) => {
+//!             queue.write_chunk_uninit(n).unwrap()
+//!         }
+//!         // Overwritten items are only reported when reading:
+//!         Err(Lagged { .. }) => unreachable!(),
 //!     };
 //!     chunk.fill_from_iter(iter)
 //! }
@@ -224,6 +232,10 @@ impl<T> Producer<T> {
     /// (containing the number of available slots) is returned.
     /// Use [`Producer::slots()`] to obtain the number of available slots beforehand.
     ///
+    /// If the requested slots would fit into the ring buffer
+    /// but some of them are protected by the resend window,
+    /// [`ChunkError::ResendWindowFull`] is returned instead of [`ChunkError::TooFewSlots`].
+    ///
     /// # Safety
     ///
     /// This function itself is safe, as is [`WriteChunkUninit::fill_from_iter()`].
@@ -234,21 +246,28 @@ impl<T> Producer<T> {
     /// For a safe alternative that provides mutable slices of [`Default`]-initialized slots,
     /// see [`Producer::write_chunk()`].
     pub fn write_chunk_uninit(&mut self, n: usize) -> Result<WriteChunkUninit<'_, T>, ChunkError> {
-        let tail = self.cached_tail.get();
-
         // Check if the queue has *possibly* not enough slots.
-        if self.buffer.capacity - self.buffer.distance(self.cached_head.get(), tail) < n {
+        if self.usable_slots(self.cached_head.get()) < n {
             // Refresh the head ...
-            let head = self.buffer.head.load(Ordering::Acquire);
-            self.cached_head.set(head);
+            let head = self.refresh_head();
 
             // ... and check if there *really* are not enough slots.
-            let slots = self.buffer.capacity - self.buffer.distance(head, tail);
+            let slots = self.usable_slots(head);
             if slots < n {
-                return Err(ChunkError::TooFewSlots(slots));
+                let (retained, unoccupied) = self.occupancy();
+                return Err(if unoccupied < n {
+                    ChunkError::TooFewSlots(slots)
+                } else {
+                    ChunkError::ResendWindowFull {
+                        usable: slots,
+                        retained,
+                    }
+                });
             }
         }
-        let tail = self.buffer.collapse_position(tail);
+        self.buffer
+            .begin_writes(self.cached_tail.get(), self.tail_seq.get(), n);
+        let tail = self.buffer.collapse_position(self.cached_tail.get());
         let first_len = n.min(self.buffer.capacity - tail);
         Ok(WriteChunkUninit {
             // SAFETY: tail has been updated to a valid position.
@@ -279,17 +298,25 @@ impl<T> Consumer<T> {
     /// ([`ReadChunk::into_iter()`] can be used to explicitly turn it into an [`Iterator`]).
     /// All moved items are automatically made available to be written again by the [`Producer`].
     ///
+    /// To keep the items in the resend window for replay,
+    /// use [`ReadChunk::commit_retaining()`] or [`ReadChunk::into_iter_retaining()`] instead.
+    ///
     /// # Errors
     ///
     /// If not enough slots are available, an error
     /// (containing the number of available slots) is returned.
     /// Use [`Consumer::slots()`] to obtain the number of available slots beforehand.
     ///
+    /// If the [`Producer`] has overwritten unread items
+    /// (see [`OverflowPolicy::OverwriteOldest`](crate::OverflowPolicy::OverwriteOldest)),
+    /// [`ChunkError::Lagged`] is returned once, like [`PopError::Lagged`](crate::PopError::Lagged)
+    /// is returned from [`Consumer::pop()`].
+    ///
     /// # Examples
     ///
     /// See the documentation of the [`chunks`](crate::chunks#examples) module.
     pub fn read_chunk(&mut self, n: usize) -> Result<ReadChunk<'_, T>, ChunkError> {
-        let head = self.cached_head.get();
+        let head = self.unread_head();
 
         // Check if the queue has *possibly* not enough slots.
         if self.buffer.distance(head, self.cached_tail.get()) < n {
@@ -298,13 +325,25 @@ impl<T> Consumer<T> {
             self.cached_tail.set(tail);
 
             // ... and check if there *really* are not enough slots.
-            let slots = self.buffer.distance(head, tail);
-            if slots < n {
+            // The producer might have overwritten items after `head` has been loaded.
+            let slots = self.buffer.distance(head, tail).min(self.buffer.capacity);
+            // Overwritten items are reported first, see below.
+            if slots < n && self.lagged.get() == 0 && head == self.cached_head.get() {
                 return Err(ChunkError::TooFewSlots(slots));
             }
         }
 
-        let head = self.buffer.collapse_position(head);
+        // Unless items have been overwritten in the meantime, there are enough slots.
+        // An empty chunk doesn't have to be protected from being overwritten.
+        if n != 0 {
+            self.pin();
+        }
+        if let Some((first, skipped)) = self.take_lagged() {
+            self.unpin();
+            return Err(ChunkError::Lagged { first, skipped });
+        }
+
+        let head = self.buffer.collapse_position(self.cached_head.get());
         let first_len = n.min(self.buffer.capacity - head);
         Ok(ReadChunk {
             // SAFETY: head has been updated to a valid position.
@@ -500,9 +539,13 @@ impl<T> WriteChunkUninit<'_, T> {
 
     unsafe fn commit_unchecked(self, n: usize) -> usize {
         let p = self.producer;
+        p.buffer
+            .end_writes(p.cached_tail.get(), p.tail_seq.get(), n);
         let tail = p.buffer.increment(p.cached_tail.get(), n);
         p.buffer.tail.store(tail, Ordering::Release);
+        p.buffer.notify_consumer();
         p.cached_tail.set(tail);
+        p.tail_seq.set(p.tail_seq.get() + n as u64);
         n
     }
 
@@ -520,7 +563,7 @@ impl<T> WriteChunkUninit<'_, T> {
     /// ```
     /// use rtrb::{RingBuffer, PopError};
     ///
-    /// let (mut p, mut c) = RingBuffer::new(4);
+    /// let (mut p, mut c) = RingBuffer::new(4, 0);
     ///
     /// if let Ok(chunk) = p.write_chunk_uninit(3) {
     ///     assert_eq!(chunk.fill_from_iter([10, 20]), 2);
@@ -540,7 +583,7 @@ impl<T> WriteChunkUninit<'_, T> {
     /// ```
     /// use rtrb::{RingBuffer, PopError};
     ///
-    /// let (mut p, mut c) = RingBuffer::new(4);
+    /// let (mut p, mut c) = RingBuffer::new(4, 0);
     ///
     /// let mut it = vec![10, 20, 30].into_iter();
     /// if let Ok(chunk) = p.write_chunk_uninit(2) {
@@ -624,7 +667,7 @@ pub struct ReadChunk<'a, T> {
 // It is therefore safe to move it to another thread.
 unsafe impl<T: Send> Send for ReadChunk<'_, T> {}
 
-impl<T> ReadChunk<'_, T> {
+impl<'a, T> ReadChunk<'a, T> {
     /// Returns two slices for reading from the requested slots.
     ///
     /// The first slice can only be empty if `0` slots have been requested.
@@ -658,8 +701,26 @@ impl<T> ReadChunk<'_, T> {
     /// there are some scenarios where it might be desirable to perform
     /// operations on the data in-place without copying it to a separate buffer
     /// (e.g. streaming decryption), in which case this version can be used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chunk contains items that are delivered again after
+    /// [`Consumer::rewind()`], because the [`Producer`] might concurrently read them
+    /// (see [`Producer::history()`]).
+    /// It also panics if the chunk is not empty while the [`HistoryReader`](crate::HistoryReader)
+    /// exists, which might concurrently read the items as well.
     #[must_use]
     pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let c = self.consumer;
+        assert!(
+            self.is_empty() || c.head_seq.get() >= c.rewound_seq.get(),
+            "rewound items cannot be accessed mutably"
+        );
+        // This pairs with the store in HistoryReader::drop().
+        assert!(
+            self.is_empty() || !c.buffer.history_reader.load(Ordering::Acquire),
+            "items cannot be accessed mutably while a HistoryReader exists"
+        );
         // SAFETY: The pointers and lengths have been computed correctly in read_chunk().
         unsafe {
             (
@@ -671,6 +732,9 @@ impl<T> ReadChunk<'_, T> {
 
     /// Drops the first `n` slots of the chunk, making the space available for writing again.
     ///
+    /// Items that are retained in the resend window
+    /// (see [`RingBuffer::with_replay()`]) are dropped as well.
+    ///
     /// # Panics
     ///
     /// Panics if `n` is greater than the number of slots in the chunk.
@@ -693,7 +757,7 @@ impl<T> ReadChunk<'_, T> {
     ///
     /// // Scope to limit lifetime of ring buffer
     /// {
-    ///     let (mut p, mut c) = RingBuffer::new(2);
+    ///     let (mut p, mut c) = RingBuffer::new(2, 0);
     ///
     ///     assert!(p.push(Thing).is_ok()); // 1
     ///     assert!(p.push(Thing).is_ok()); // 2
@@ -735,6 +799,110 @@ impl<T> ReadChunk<'_, T> {
     }
 
     unsafe fn commit_unchecked(self, n: usize) -> usize {
+        // SAFETY: The caller must make sure that there are n initialized elements.
+        unsafe { self.drop_prefix(n) };
+        let c = self.consumer;
+        let head = c.buffer.increment(c.cached_head.get(), n);
+        // Committed items are dropped, therefore older items cannot be retained either.
+        c.clear_retained(c.cached_head.get(), head);
+        c.store_head(head);
+        c.head_seq.set(c.head_seq.get() + n as u64);
+        n
+    }
+
+    /// Makes the first `n` slots of the chunk available for writing again,
+    /// but keeps their items in the resend window.
+    ///
+    /// This is the counterpart of [`Consumer::pop()`] for chunks:
+    /// the committed items can be re-delivered after [`Consumer::rewind()`]
+    /// and are only dropped once they fall out of the resend window
+    /// (or are acknowledged, see [`RingBuffer::with_acks()`]).
+    /// Without a resend window, this is the same as [`ReadChunk::commit()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the number of slots in the chunk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::new(4, 2);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// c.read_chunk(2).unwrap().commit_retaining(2);
+    /// assert_eq!(c.retained(), 2);
+    /// assert_eq!(c.rewind(2), Ok(()));
+    /// assert_eq!(c.read_chunk(2).unwrap().as_slices(), (&[10, 20][..], &[][..]));
+    /// ```
+    pub fn commit_retaining(self, n: usize) {
+        assert!(n <= self.len(), "cannot commit more than chunk size");
+        // SAFETY: self.len() initialized elements have been obtained in read_chunk().
+        unsafe { self.commit_retaining_unchecked(n) };
+    }
+
+    /// Returns an iterator over references to the items in the chunk.
+    ///
+    /// Nothing is committed, use [`ReadChunk::commit_retaining()`] afterwards
+    /// to keep the items in the resend window.
+    pub fn iter(&self) -> core::iter::Chain<core::slice::Iter<'_, T>, core::slice::Iter<'_, T>> {
+        let (first, second) = self.as_slices();
+        first.iter().chain(second)
+    }
+
+    /// Turns the chunk into an iterator that yields clones of the items.
+    ///
+    /// When the iterator is dropped, all iterated slots are committed with
+    /// [`ReadChunk::commit_retaining()`], which keeps the original items in the resend window.
+    /// Non-iterated items remain in the ring buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::new(5, 2);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// let v: Vec<_> = c.read_chunk(3).unwrap().into_iter_retaining().take(2).collect();
+    /// assert_eq!(v, [10, 20]);
+    /// assert_eq!(c.retained(), 2);
+    /// assert_eq!(c.slots(), 1);
+    /// ```
+    pub fn into_iter_retaining(self) -> ReadChunkRetainingIter<'a, T>
+    where
+        T: Clone,
+    {
+        ReadChunkRetainingIter {
+            chunk: self,
+            iterated: 0,
+        }
+    }
+
+    unsafe fn commit_retaining_unchecked(&self, n: usize) {
+        let c = self.consumer;
+        let head = c.buffer.increment(c.cached_head.get(), n);
+        if c.buffer.resend_window() == 0 {
+            // SAFETY: The caller must make sure that there are n initialized elements.
+            unsafe { self.drop_prefix(n) };
+        } else {
+            c.release_retained(head, c.buffer.resend_window());
+        }
+        c.store_head(head);
+        c.head_seq.set(c.head_seq.get() + n as u64);
+    }
+
+    /// Drops the first `n` elements.
+    ///
+    /// Those slots must be initialized.
+    unsafe fn drop_prefix(&self, n: usize) {
+        // NB: Rewound items without drop glue might be read concurrently,
+        // see Producer::history().
+        if !core::mem::needs_drop::<T>() {
+            return;
+        }
         let first_len = self.first_len.min(n);
         for i in 0..first_len {
             // SAFETY: The caller must make sure that there are n initialized elements.
@@ -745,11 +913,6 @@ impl<T> ReadChunk<'_, T> {
             // SAFETY: The caller must make sure that there are n initialized elements.
             unsafe { self.second_ptr.add(i).drop_in_place() };
         }
-        let c = self.consumer;
-        let head = c.buffer.increment(c.cached_head.get(), n);
-        c.buffer.head.store(head, Ordering::Release);
-        c.cached_head.set(head);
-        n
     }
 
     /// Returns the number of slots in the chunk.
@@ -763,6 +926,14 @@ impl<T> ReadChunk<'_, T> {
     pub fn is_empty(&self) -> bool {
         self.first_len == 0
     }
+
+    /// Returns the first slice without committing anything,
+    /// borrowing the [`Consumer`] for as long as the chunk would have.
+    #[cfg(feature = "std")]
+    pub(crate) fn into_first_slice(self) -> &'a [T] {
+        // SAFETY: The pointer and length have been computed correctly in read_chunk().
+        unsafe { core::slice::from_raw_parts(self.first_ptr, self.first_len) }
+    }
 }
 
 impl<'a, T> IntoIterator for ReadChunk<'a, T> {
@@ -801,8 +972,10 @@ impl<T> Drop for ReadChunkIntoIter<'_, T> {
     fn drop(&mut self) {
         let c = &self.chunk.consumer;
         let head = c.buffer.increment(c.cached_head.get(), self.iterated);
-        c.buffer.head.store(head, Ordering::Release);
-        c.cached_head.set(head);
+        // Iterated items have been moved out, therefore older items cannot be retained either.
+        c.clear_retained(c.cached_head.get(), head);
+        c.store_head(head);
+        c.head_seq.set(c.head_seq.get() + self.iterated as u64);
     }
 }
 
@@ -840,15 +1013,70 @@ impl<T> ExactSizeIterator for ReadChunkIntoIter<'_, T> {}
 
 impl<T> core::iter::FusedIterator for ReadChunkIntoIter<'_, T> {}
 
+/// An iterator that yields clones of the items in a [`ReadChunk`].
+///
+/// This `struct` is created by [`ReadChunk::into_iter_retaining()`].
+///
+/// When this `struct` is dropped, the iterated slots are made available for writing again,
+/// but their items are kept in the resend window.
+/// Non-iterated items remain in the ring buffer.
+#[derive(Debug)]
+pub struct ReadChunkRetainingIter<'a, T> {
+    chunk: ReadChunk<'a, T>,
+    iterated: usize,
+}
+
+impl<T> Drop for ReadChunkRetainingIter<'_, T> {
+    /// Makes all iterated slots available for writing again, retaining their items.
+    ///
+    /// Non-iterated items remain in the ring buffer.
+    fn drop(&mut self) {
+        // SAFETY: The iterated slots are still initialized, only clones have been handed out.
+        unsafe { self.chunk.commit_retaining_unchecked(self.iterated) };
+    }
+}
+
+impl<T: Clone> Iterator for ReadChunkRetainingIter<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (first, second) = self.chunk.as_slices();
+        let item = if self.iterated < first.len() {
+            &first[self.iterated]
+        } else {
+            second.get(self.iterated - first.len())?
+        };
+        self.iterated += 1;
+        Some(item.clone())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.chunk.len() - self.iterated;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: Clone> ExactSizeIterator for ReadChunkRetainingIter<'_, T> {}
+
+impl<T: Clone> core::iter::FusedIterator for ReadChunkRetainingIter<'_, T> {}
+
 #[cfg(feature = "std")]
 impl std::io::Write for Producer<u8> {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        use ChunkError::TooFewSlots;
+        use ChunkError::{Lagged, ResendWindowFull, TooFewSlots};
         let mut chunk = match self.write_chunk_uninit(buf.len()) {
             Ok(chunk) => chunk,
-            Err(TooFewSlots(0)) => return Err(std::io::ErrorKind::WouldBlock.into()),
-            Err(TooFewSlots(n)) => self.write_chunk_uninit(n).unwrap(),
+            Err(TooFewSlots(0)) | Err(ResendWindowFull { usable: 0, .. }) => {
+                return Err(std::io::ErrorKind::WouldBlock.into())
+            }
+            Err(TooFewSlots(n)) | Err(ResendWindowFull { usable: n, .. }) => {
+                self.write_chunk_uninit(n).unwrap()
+            }
+            // Overwritten items are only reported when reading.
+            Err(Lagged { .. }) => unreachable!(),
         };
         let end = chunk.len();
         let (first, second) = chunk.as_mut_slices();
@@ -861,6 +1089,43 @@ impl std::io::Write for Producer<u8> {
         Ok(end)
     }
 
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        use ChunkError::{Lagged, ResendWindowFull, TooFewSlots};
+        let total = bufs.iter().map(|buf| buf.len()).sum();
+        let mut chunk = match self.write_chunk_uninit(total) {
+            Ok(chunk) => chunk,
+            Err(TooFewSlots(0)) | Err(ResendWindowFull { usable: 0, .. }) => {
+                return Err(std::io::ErrorKind::WouldBlock.into())
+            }
+            Err(TooFewSlots(n)) | Err(ResendWindowFull { usable: n, .. }) => {
+                self.write_chunk_uninit(n).unwrap()
+            }
+            // Overwritten items are only reported when reading.
+            Err(Lagged { .. }) => unreachable!(),
+        };
+        let end = chunk.len();
+        let (first, second) = chunk.as_mut_slices();
+        let mut bufs = bufs.iter();
+        let mut src: &[u8] = &[];
+        for dst in [first, second].iter_mut() {
+            let mut pos = 0;
+            while pos < dst.len() {
+                if src.is_empty() {
+                    // The chunk is not larger than all buffers together.
+                    src = bufs.next().unwrap();
+                    continue;
+                }
+                let n = src.len().min(dst.len() - pos);
+                src[..n].copy_to_uninit(&mut dst[pos..pos + n]);
+                src = &src[n..];
+                pos += n;
+            }
+        }
+        // SAFETY: All slots have been initialized
+        unsafe { chunk.commit_all() };
+        Ok(end)
+    }
+
     #[inline]
     fn flush(&mut self) -> std::io::Result<()> {
         // Nothing to do here.
@@ -868,15 +1133,25 @@ impl std::io::Write for Producer<u8> {
     }
 }
 
+/// Copies as many bytes as are available.
+///
+/// Read bytes are committed with [`ReadChunk::commit_retaining()`],
+/// which keeps them in the resend window (if there is one),
+/// like bytes consumed with [`std::io::BufRead`].
 #[cfg(feature = "std")]
 impl std::io::Read for Consumer<u8> {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        use ChunkError::TooFewSlots;
+        use ChunkError::{Lagged, ResendWindowFull, TooFewSlots};
         let chunk = match self.read_chunk(buf.len()) {
             Ok(chunk) => chunk,
             Err(TooFewSlots(0)) => return Err(std::io::ErrorKind::WouldBlock.into()),
             Err(TooFewSlots(n)) => self.read_chunk(n).unwrap(),
+            // The resend window only restricts writing.
+            Err(ResendWindowFull { .. }) => unreachable!(),
+            Err(e @ Lagged { .. }) => {
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, e))
+            }
         };
         let (first, second) = chunk.as_slices();
         let mid = first.len();
@@ -884,9 +1159,275 @@ impl std::io::Read for Consumer<u8> {
         // NB: If buf.is_empty(), chunk will be empty as well and the following are no-ops:
         buf[..mid].copy_from_slice(first);
         buf[mid..end].copy_from_slice(second);
-        chunk.commit_all();
+        chunk.commit_retaining(end);
         Ok(end)
     }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> std::io::Result<usize> {
+        use ChunkError::{Lagged, ResendWindowFull, TooFewSlots};
+        let total = bufs.iter().map(|buf| buf.len()).sum();
+        let chunk = match self.read_chunk(total) {
+            Ok(chunk) => chunk,
+            Err(TooFewSlots(0)) => return Err(std::io::ErrorKind::WouldBlock.into()),
+            Err(TooFewSlots(n)) => self.read_chunk(n).unwrap(),
+            // The resend window only restricts writing.
+            Err(ResendWindowFull { .. }) => unreachable!(),
+            Err(e @ Lagged { .. }) => {
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, e))
+            }
+        };
+        let (first, second) = chunk.as_slices();
+        let mut parts = [first, second];
+        let mut part = 0;
+        for buf in bufs.iter_mut() {
+            let mut pos = 0;
+            while pos < buf.len() && part < parts.len() {
+                let src = parts[part];
+                if src.is_empty() {
+                    part += 1;
+                    continue;
+                }
+                let n = src.len().min(buf.len() - pos);
+                buf[pos..pos + n].copy_from_slice(&src[..n]);
+                parts[part] = &src[n..];
+                pos += n;
+            }
+        }
+        let end = chunk.len();
+        chunk.commit_retaining(end);
+        Ok(end)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Producer<u8> {
+    /// Reads from `reader` directly into the available slots of the ring buffer.
+    ///
+    /// All available slots are passed to [`Read::read_vectored()`](std::io::Read::read_vectored)
+    /// as (up to) two buffers, which for files, pipes and sockets
+    /// results in a single `readv()` system call without an intermediate buffer.
+    /// Since [`std::io::Read`] requires initialized buffers,
+    /// slots that have never been written are filled with zeros beforehand,
+    /// which is only necessary until the ring buffer has wrapped around once.
+    ///
+    /// Returns the number of bytes that have been written into the ring buffer,
+    /// `0` means that `reader` has reached its end.
+    ///
+    /// # Errors
+    ///
+    /// If there are no slots available, an error of kind
+    /// [`WouldBlock`](std::io::ErrorKind::WouldBlock) is returned without reading anything.
+    /// Errors from `reader` are passed through, nothing is written in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::new(4, 0);
+    /// let mut reader = &b"abcdef"[..];
+    /// assert_eq!(p.fill_from(&mut reader).unwrap(), 4);
+    /// assert_eq!(c.pop(), Ok(b'a'));
+    /// assert_eq!(p.fill_from(&mut reader).unwrap(), 1);
+    /// assert_eq!(p.fill_from(&mut reader).unwrap_err().kind(), std::io::ErrorKind::WouldBlock);
+    /// ```
+    pub fn fill_from(&mut self, reader: &mut impl std::io::Read) -> std::io::Result<usize> {
+        let slots = self.slots();
+        if slots == 0 {
+            return Err(std::io::ErrorKind::WouldBlock.into());
+        }
+        // Slots are written in order, the ones before this index have been written before.
+        let initialized = self.tail_seq.get().min(self.buffer.capacity as u64) as usize;
+        let tail = self.buffer.collapse_position(self.cached_tail.get());
+        let mut chunk = self
+            .write_chunk_uninit(slots)
+            .expect("slots have just been checked");
+        let (first, second) = chunk.as_mut_slices();
+        let n = reader.read_vectored(&mut [
+            std::io::IoSliceMut::new(initialize_bytes(first, tail, initialized)),
+            std::io::IoSliceMut::new(initialize_bytes(second, 0, initialized)),
+        ])?;
+        // SAFETY: All slots of the chunk have been initialized.
+        unsafe { chunk.commit(n) };
+        Ok(n)
+    }
+}
+
+/// Zeroes the slots in `slice` that have never been written, starting at index `initialized`.
+///
+/// `start` is the index of the first slot of `slice` in the ring buffer.
+#[cfg(feature = "std")]
+fn initialize_bytes(slice: &mut [MaybeUninit<u8>], start: usize, initialized: usize) -> &mut [u8] {
+    let written = initialized.saturating_sub(start).min(slice.len());
+    for slot in &mut slice[written..] {
+        *slot = MaybeUninit::new(0);
+    }
+    // SAFETY: Bytes don't need to be dropped, therefore slots that have been written once
+    // stay initialized (even after being read), all other slots have just been zeroed.
+    unsafe { &mut *(slice as *mut [MaybeUninit<u8>] as *mut [u8]) }
+}
+
+#[cfg(feature = "std")]
+impl Consumer<u8> {
+    /// Writes the available bytes directly from the ring buffer into `writer`.
+    ///
+    /// The available bytes are passed to [`Write::write_vectored()`](std::io::Write::write_vectored)
+    /// as (up to) two buffers, which for files, pipes and sockets
+    /// results in a single `writev()` system call without an intermediate buffer.
+    /// The written bytes are committed with [`ReadChunk::commit_retaining()`],
+    /// which keeps them in the resend window (if there is one).
+    ///
+    /// Returns the number of bytes that have been written into `writer`.
+    ///
+    /// # Errors
+    ///
+    /// If there are no bytes available, an error of kind
+    /// [`WouldBlock`](std::io::ErrorKind::WouldBlock) is returned without writing anything.
+    /// Errors from `writer` are passed through, nothing is consumed in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::new(4, 0);
+    /// let mut writer = Vec::new();
+    /// assert_eq!(p.fill_from(&mut &b"abcd"[..]).unwrap(), 4);
+    /// assert_eq!(c.pop(), Ok(b'a'));
+    /// assert_eq!(p.push(b'e'), Ok(4));
+    /// // The bytes wrap around, but they are written in one go.
+    /// assert_eq!(c.drain_into(&mut writer).unwrap(), 4);
+    /// assert_eq!(writer, b"bcde");
+    /// ```
+    pub fn drain_into(&mut self, writer: &mut impl std::io::Write) -> std::io::Result<usize> {
+        let slots = self.slots();
+        let chunk = match self.read_chunk(slots) {
+            Ok(chunk) if chunk.is_empty() => return Err(std::io::ErrorKind::WouldBlock.into()),
+            Ok(chunk) => chunk,
+            Err(e @ ChunkError::Lagged { .. }) => {
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, e))
+            }
+            // More slots might have become available, but never fewer.
+            Err(_) => unreachable!(),
+        };
+        let (first, second) = chunk.as_slices();
+        let n = writer
+            .write_vectored(&[std::io::IoSlice::new(first), std::io::IoSlice::new(second)])?;
+        chunk.commit_retaining(n);
+        Ok(n)
+    }
+}
+
+/// Provides the first contiguous part of the available bytes without copying.
+///
+/// The ring buffer wraps around, so [`fill_buf()`](std::io::BufRead::fill_buf)
+/// might return only a part of the available bytes,
+/// the rest is returned after consuming it.
+/// Consumed bytes are committed with [`ReadChunk::commit_retaining()`],
+/// which keeps them in the resend window (if there is one).
+///
+/// Like with [`std::io::Read`], an error of kind [`WouldBlock`](std::io::ErrorKind::WouldBlock)
+/// is returned if no bytes are available.
+///
+/// # Examples
+///
+/// ```
+/// use rtrb::RingBuffer;
+/// use std::io::{BufRead, Write};
+///
+/// let (mut p, mut c) = RingBuffer::new(16, 0);
+/// p.write_all(b"first\nsecond\n").unwrap();
+/// let mut line = String::new();
+/// c.read_line(&mut line).unwrap();
+/// assert_eq!(line, "first\n");
+/// assert_eq!(c.fill_buf().unwrap(), b"second\n");
+/// ```
+#[cfg(feature = "std")]
+impl std::io::BufRead for Consumer<u8> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        use ChunkError::{Lagged, ResendWindowFull, TooFewSlots};
+        let n = self.slots();
+        match self.read_chunk(n) {
+            Ok(chunk) if chunk.is_empty() => Err(std::io::ErrorKind::WouldBlock.into()),
+            Ok(chunk) => Ok(chunk.into_first_slice()),
+            // More slots might have become available, but never fewer.
+            Err(TooFewSlots(_)) | Err(ResendWindowFull { .. }) => unreachable!(),
+            Err(e @ Lagged { .. }) => Err(std::io::Error::new(std::io::ErrorKind::Other, e)),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.read_chunk(amt)
+            .expect("cannot consume more than the available bytes")
+            .commit_retaining(amt);
+    }
+}
+
+/// Moves the read position within the retained and the pending bytes.
+///
+/// The position is the sequence number of the next byte to be read,
+/// see [`Consumer::head()`].
+/// [`SeekFrom::End`](std::io::SeekFrom::End) is relative to the end of the pending bytes.
+///
+/// Seeking backwards moves into the bytes retained in the resend window,
+/// like [`Consumer::rewind()`].
+/// Seeking forwards skips pending bytes, which are committed with
+/// [`ReadChunk::commit_retaining()`] and can therefore be reached again by seeking backwards.
+/// Seeking before the retained bytes or past the pending bytes returns an error
+/// of kind [`InvalidInput`](std::io::ErrorKind::InvalidInput)
+/// (containing [`RewindError`](crate::RewindError) or [`ChunkError::TooFewSlots`],
+/// respectively) and the position stays unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use rtrb::RingBuffer;
+/// use std::io::{BufRead, Seek, SeekFrom, Write};
+///
+/// let (mut p, mut c) = RingBuffer::new(12, 4);
+/// p.write_all(b"header").unwrap();
+/// assert_eq!(c.seek(SeekFrom::Current(4)).unwrap(), 4);
+/// assert_eq!(c.seek(SeekFrom::Current(-4)).unwrap(), 0);
+/// assert_eq!(c.fill_buf().unwrap(), b"header");
+/// c.consume(4);
+/// assert_eq!(c.seek(SeekFrom::End(-1)).unwrap(), 5);
+/// assert!(c.seek(SeekFrom::Current(2)).is_err());
+/// // Only the last 4 bytes are retained:
+/// assert!(c.seek(SeekFrom::Start(0)).is_err());
+/// assert_eq!(c.seek(SeekFrom::Start(1)).unwrap(), 1);
+/// ```
+#[cfg(feature = "std")]
+impl std::io::Seek for Consumer<u8> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        use std::io::{Error, ErrorKind, SeekFrom};
+        // This has to come first, it might skip overwritten bytes.
+        let pending = self.slots();
+        let offset = match pos {
+            SeekFrom::Start(n) => i128::from(n) - i128::from(self.head()),
+            SeekFrom::Current(n) => i128::from(n),
+            SeekFrom::End(n) => pending as i128 + i128::from(n),
+        };
+        if offset < 0 {
+            let retained = self.retained();
+            if -offset > retained as i128 {
+                let e = crate::RewindError::TooFewItems(retained);
+                return Err(Error::new(ErrorKind::InvalidInput, e));
+            }
+            self.rewind(-offset as usize)
+                .expect("retained bytes have just been checked");
+        } else if offset > pending as i128 {
+            let e = ChunkError::TooFewSlots(pending);
+            return Err(Error::new(ErrorKind::InvalidInput, e));
+        } else {
+            let n = offset as usize;
+            match self.read_chunk(n) {
+                Ok(chunk) => chunk.commit_retaining(n),
+                Err(e @ ChunkError::Lagged { .. }) => return Err(Error::new(ErrorKind::Other, e)),
+                Err(_) => unreachable!(),
+            }
+        }
+        Ok(self.head())
+    }
 }
 
 /// Error type for [`Consumer::read_chunk()`], [`Producer::write_chunk()`]
@@ -896,7 +1437,32 @@ pub enum ChunkError {
     /// Fewer than the requested number of slots were available.
     ///
     /// Contains the number of slots that were available.
+    /// When writing, slots protected by the resend window are not counted as available.
     TooFewSlots(usize),
+    /// Fewer than the requested number of slots were available for writing,
+    /// but the ring buffer would have enough room if it weren't for the resend window.
+    ///
+    /// This means that the [`Consumer`] keeps up, but the protected history is in the way.
+    /// If the ring buffer was created with [`RingBuffer::with_acks()`],
+    /// the retained items are waiting to be acknowledged.
+    ResendWindowFull {
+        /// The number of slots that were available for writing.
+        usable: usize,
+        /// The number of consumed items that were retained in the resend window.
+        retained: usize,
+    },
+    /// Unread items have been overwritten,
+    /// see [`OverflowPolicy::OverwriteOldest`](crate::OverflowPolicy::OverwriteOldest).
+    ///
+    /// This is only returned (once) by [`Consumer::read_chunk()`],
+    /// the following call provides the oldest remaining items.
+    /// See also [`PopError::Lagged`](crate::PopError::Lagged).
+    Lagged {
+        /// The sequence number of the first overwritten item.
+        first: u64,
+        /// The number of overwritten items.
+        skipped: u64,
+    },
 }
 
 #[cfg(feature = "std")]
@@ -908,6 +1474,19 @@ impl fmt::Display for ChunkError {
             ChunkError::TooFewSlots(n) => {
                 alloc::format!("only {} slots available in ring buffer", n).fmt(f)
             }
+            ChunkError::ResendWindowFull { usable, retained } => alloc::format!(
+                "only {} slots available in ring buffer ({} items retained in resend window)",
+                usable,
+                retained
+            )
+            .fmt(f),
+            ChunkError::Lagged { first, skipped } => alloc::format!(
+                "{} items overwritten in ring buffer (sequence numbers {}..{})",
+                skipped,
+                first,
+                first + skipped
+            )
+            .fmt(f),
         }
     }
 }
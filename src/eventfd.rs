@@ -0,0 +1,241 @@
+//! Notification via `eventfd` for integration with `epoll`-based event loops.
+//!
+//! Each side can enable its own eventfd, which is then signaled by the other side:
+//! the [`Producer`] signals the [`Consumer`]'s eventfd after writing
+//! and the [`Consumer`] signals the [`Producer`]'s eventfd after freeing slots
+//! (including acknowledgements and changes of the resend window).
+//! Signals are coalesced: after the eventfd has been written once,
+//! it is not written again until the waiting side has called `reset_eventfd()`.
+//! Therefore, the signaling side makes at most one system call per wakeup.
+//!
+//! This module is only available with the `eventfd` feature on Linux.
+
+use core::sync::atomic::{fence, AtomicBool, AtomicI32, Ordering};
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+use crate::{Consumer, Producer};
+
+/// An optional eventfd for one side of the ring buffer.
+#[derive(Debug)]
+pub(crate) struct EventFd {
+    /// The file descriptor, `-1` if it has not been enabled.
+    fd: AtomicI32,
+    /// Set by the signaling side when it writes to the eventfd,
+    /// cleared by the waiting side after reading from it.
+    signaled: AtomicBool,
+}
+
+impl EventFd {
+    pub(crate) fn new() -> Self {
+        EventFd {
+            fd: AtomicI32::new(-1),
+            signaled: AtomicBool::new(false),
+        }
+    }
+
+    /// Creates the eventfd (unless that has happened before).
+    ///
+    /// It is initially signaled, because the condition might already be fulfilled.
+    fn enable(&self) -> io::Result<()> {
+        if self.fd.load(Ordering::Relaxed) >= 0 {
+            return Ok(());
+        }
+        // SAFETY: This has no memory safety requirements.
+        let fd = unsafe { libc::eventfd(1, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.signaled.store(true, Ordering::Relaxed);
+        self.fd.store(fd, Ordering::Release);
+        Ok(())
+    }
+
+    fn raw_fd(&self, side: &str) -> RawFd {
+        let fd = self.fd.load(Ordering::Relaxed);
+        assert!(fd >= 0, "{}::enable_eventfd() has not been called", side);
+        fd
+    }
+
+    /// Writes to the eventfd, unless it is not enabled or already signaled.
+    ///
+    /// This has to be called after the new position has been stored.
+    pub(crate) fn signal(&self) {
+        let fd = self.fd.load(Ordering::Acquire);
+        if fd < 0 {
+            return;
+        }
+        // This pairs with the fence in reset().
+        fence(Ordering::SeqCst);
+        if self.signaled.load(Ordering::Relaxed) || self.signaled.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let value: u64 = 1;
+        // SAFETY: `value` is valid for reading 8 bytes.
+        // This can only fail if the counter would overflow, which cannot happen here.
+        unsafe { libc::write(fd, &value as *const u64 as *const libc::c_void, 8) };
+    }
+
+    /// Reads from the eventfd, which makes it non-readable until it is signaled again.
+    ///
+    /// This has to be called before checking the ring buffer.
+    fn reset(&self) -> io::Result<()> {
+        let fd = self.fd.load(Ordering::Relaxed);
+        if fd < 0 || !self.signaled.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let mut value: u64 = 0;
+        // SAFETY: `value` is valid for writing 8 bytes.
+        let result = unsafe { libc::read(fd, &mut value as *mut u64 as *mut libc::c_void, 8) };
+        if result < 0 {
+            let error = io::Error::last_os_error();
+            if error.kind() != io::ErrorKind::WouldBlock {
+                return Err(error);
+            }
+        }
+        // The eventfd must be empty before the other side is allowed to write again.
+        self.signaled.store(false, Ordering::Relaxed);
+        // This pairs with the fence in signal().
+        fence(Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl Drop for EventFd {
+    fn drop(&mut self) {
+        let fd = *self.fd.get_mut();
+        if fd >= 0 {
+            // SAFETY: The file descriptor is owned by `self`.
+            unsafe { libc::close(fd) };
+        }
+    }
+}
+
+impl<T> Producer<T> {
+    /// Creates an eventfd that becomes readable when the [`Consumer`] has freed slots.
+    ///
+    /// Afterwards, the file descriptor can be obtained with [`AsRawFd::as_raw_fd()`]
+    /// (which panics before) and registered with `epoll` (e.g. via `mio`).
+    /// It is initially readable, and once it has become readable,
+    /// it stays readable until [`Producer::reset_eventfd()`] is called.
+    /// Calling this again has no effect.
+    ///
+    /// Only available with the `eventfd` feature on Linux.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the `eventfd()` system call.
+    pub fn enable_eventfd(&mut self) -> io::Result<()> {
+        self.buffer.producer_eventfd.enable()
+    }
+
+    /// Makes the eventfd non-readable until the [`Consumer`] frees slots again.
+    ///
+    /// This has to be called after the eventfd has become readable
+    /// and *before* writing into the ring buffer,
+    /// otherwise a notification might be missed.
+    /// Nothing happens if the eventfd is not readable or hasn't been enabled.
+    ///
+    /// Only available with the `eventfd` feature on Linux.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the `read()` system call.
+    pub fn reset_eventfd(&mut self) -> io::Result<()> {
+        self.buffer.producer_eventfd.reset()
+    }
+}
+
+/// Provides the eventfd created with [`Producer::enable_eventfd()`].
+///
+/// Only available with the `eventfd` feature on Linux.
+impl<T> AsRawFd for Producer<T> {
+    /// Returns the eventfd created with [`Producer::enable_eventfd()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Producer::enable_eventfd()`] has not been called.
+    fn as_raw_fd(&self) -> RawFd {
+        self.buffer.producer_eventfd.raw_fd("Producer")
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Creates an eventfd that becomes readable when the [`Producer`] has written items.
+    ///
+    /// Afterwards, the file descriptor can be obtained with [`AsRawFd::as_raw_fd()`]
+    /// (which panics before) and registered with `epoll` (e.g. via `mio`).
+    /// It is initially readable, and once it has become readable,
+    /// it stays readable until [`Consumer::reset_eventfd()`] is called.
+    /// Calling this again has no effect.
+    ///
+    /// Only available with the `eventfd` feature on Linux.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the `eventfd()` system call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let (mut p, mut c) = RingBuffer::new(4, 0);
+    /// c.enable_eventfd().unwrap();
+    /// let fd = c.as_raw_fd();
+    ///
+    /// let t = std::thread::spawn(move || {
+    ///     for i in 0..10 {
+    ///         while p.push(i).is_err() {
+    ///             std::thread::yield_now();
+    ///         }
+    ///     }
+    /// });
+    /// let mut received = Vec::new();
+    /// while received.len() < 10 {
+    ///     // This would typically be done by an event loop.
+    ///     let mut pollfd = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+    ///     assert_eq!(unsafe { libc::poll(&mut pollfd, 1, -1) }, 1);
+    ///     c.reset_eventfd().unwrap();
+    ///     while let Ok(value) = c.pop() {
+    ///         received.push(value);
+    ///     }
+    /// }
+    /// assert_eq!(received, (0..10).collect::<Vec<_>>());
+    /// t.join().unwrap();
+    /// ```
+    pub fn enable_eventfd(&mut self) -> io::Result<()> {
+        self.buffer.consumer_eventfd.enable()
+    }
+
+    /// Makes the eventfd non-readable until the [`Producer`] writes items again.
+    ///
+    /// This has to be called after the eventfd has become readable
+    /// and *before* reading from the ring buffer,
+    /// otherwise a notification might be missed.
+    /// Nothing happens if the eventfd is not readable or hasn't been enabled.
+    ///
+    /// Only available with the `eventfd` feature on Linux.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the `read()` system call.
+    pub fn reset_eventfd(&mut self) -> io::Result<()> {
+        self.buffer.consumer_eventfd.reset()
+    }
+}
+
+/// Provides the eventfd created with [`Consumer::enable_eventfd()`].
+///
+/// Only available with the `eventfd` feature on Linux.
+impl<T> AsRawFd for Consumer<T> {
+    /// Returns the eventfd created with [`Consumer::enable_eventfd()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Consumer::enable_eventfd()`] has not been called.
+    fn as_raw_fd(&self) -> RawFd {
+        self.buffer.consumer_eventfd.raw_fd("Consumer")
+    }
+}
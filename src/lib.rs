@@ -9,7 +9,8 @@
 //! Reading from and writing into the ring buffer is *lock-free* and *wait-free*.
 //! All reading and writing functions return immediately.
 //! Attempts to write to a full buffer return an error;
-//! values inside the buffer are *not* overwritten.
+//! values inside the buffer are *not* overwritten
+//! (unless requested with [`OverflowPolicy::OverwriteOldest`]).
 //! Attempts to read from an empty buffer return an error as well.
 //! Only a single thread can write into the ring buffer and a single thread
 //! (typically a different one) can read from the ring buffer.
@@ -26,10 +27,10 @@
 //! ```
 //! use rtrb::{RingBuffer, PushError, PopError};
 //!
-//! let (mut producer, mut consumer) = RingBuffer::new(2);
+//! let (mut producer, mut consumer) = RingBuffer::new(2, 0);
 //!
-//! assert_eq!(producer.push(10), Ok(()));
-//! assert_eq!(producer.push(20), Ok(()));
+//! assert_eq!(producer.push(10), Ok(0));
+//! assert_eq!(producer.push(20), Ok(1));
 //! assert_eq!(producer.push(30), Err(PushError::Full(30)));
 //!
 //! std::thread::spawn(move || {
@@ -43,6 +44,21 @@
 //! for examples that write multiple items at once with
 //! [`Producer::write_chunk_uninit()`] and [`Producer::write_chunk()`]
 //! and read multiple items with [`Consumer::read_chunk()`].
+//!
+//! With the `std` feature, the [`blocking`] module provides methods
+//! that wait for the other thread, like [`Producer::push_blocking()`]
+//! and [`Consumer::pop_blocking()`].
+//!
+//! With the `async` feature, [`Consumer`] implements
+//! [`Stream`](https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html)
+//! and [`Producer`] implements
+//! [`Sink`](https://docs.rs/futures-sink/0.3/futures_sink/trait.Sink.html).
+//! Instead of blocking a thread, these wake the task that is waiting for the other side.
+//! The `tokio` feature additionally implements tokio's `AsyncWrite` for [`Producer<u8>`]
+//! and `AsyncRead` and `AsyncBufRead` for [`Consumer<u8>`].
+//!
+//! With the `eventfd` feature on Linux, both sides can enable an eventfd
+//! (e.g. `Consumer::enable_eventfd()`) to be woken by an `epoll`-based event loop.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(rust_2018_idioms)]
@@ -58,7 +74,8 @@ use core::cell::Cell;
 use core::fmt;
 use core::marker::PhantomData;
 use core::mem::{ManuallyDrop, MaybeUninit};
-use core::sync::atomic::{AtomicUsize, Ordering};
+use core::ops::{Bound, Range, RangeBounds};
+use core::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
 
 #[allow(dead_code, clippy::undocumented_unsafe_blocks)]
 mod cache_padded;
@@ -66,10 +83,31 @@ use cache_padded::CachePadded;
 
 pub mod chunks;
 
+#[cfg(feature = "std")]
+pub mod blocking;
+
+#[cfg(feature = "async")]
+mod asynchronous;
+
+#[cfg(all(feature = "eventfd", target_os = "linux"))]
+mod eventfd;
+
 // This is used in the documentation.
 #[allow(unused_imports)]
 use chunks::WriteChunkUninit;
 
+/// The number of bits in a `usize`, used for the selective acknowledgement bitmap.
+const USIZE_BITS: usize = core::mem::size_of::<usize>() * 8;
+
+/// Set in `head` while the [`Consumer`] is accessing unread items,
+/// which prevents the [`Producer`] from overwriting them.
+///
+/// This is only used with [`OverflowPolicy::OverwriteOldest`].
+const PINNED: usize = 1 << (USIZE_BITS - 1);
+
+/// The bits of a sequence number (or stamp) that are compared by the [`HistoryReader`].
+const STAMP_MASK: usize = !0 >> 1;
+
 /// A bounded single-producer single-consumer (SPSC) queue.
 ///
 /// Elements can be written with a [`Producer`] and read with a [`Consumer`],
@@ -81,6 +119,7 @@ pub struct RingBuffer<T> {
     /// The head of the queue.
     ///
     /// This integer is in range `0 .. 2 * capacity`.
+    /// With [`OverflowPolicy::OverwriteOldest`], the [`PINNED`] bit may be set as well.
     head: CachePadded<AtomicUsize>,
 
     /// The tail of the queue.
@@ -91,10 +130,88 @@ pub struct RingBuffer<T> {
     /// The buffer holding slots.
     data_ptr: *mut T,
 
+    /// The start of the retained items.
+    ///
+    /// Slots between this position and `head` have already been consumed,
+    /// but their items are kept to be re-delivered after [`Consumer::rewind()`].
+    /// This is only maintained if `resend_window` is non-zero.
+    ///
+    /// If `ack_required` is set, this is the acknowledgement position
+    /// and the [`Producer`] uses it instead of `head`.
+    ///
+    /// This integer is in range `0 .. 2 * capacity`.
+    release: CachePadded<AtomicUsize>,
+
     /// The queue capacity.
     capacity: usize,
 
-    resend_window: usize,
+    /// The number of consumed slots that are protected from being overwritten.
+    ///
+    /// This can only be changed by the [`Consumer`], see [`Consumer::set_resend_window()`].
+    /// The [`Producer`] has to load it *after* loading `head`.
+    resend_window: AtomicUsize,
+
+    /// Whether consumed items are only released with [`Consumer::ack()`].
+    ///
+    /// This is only set if the ring buffer was created with [`RingBuffer::with_acks()`],
+    /// in which case `resend_window` is the whole capacity.
+    ack_required: bool,
+
+    /// Used to keep a copy of consumed items in the resend window.
+    ///
+    /// This is only available if the ring buffer was created with
+    /// [`RingBuffer::with_replay()`].
+    clone_fn: Option<fn(&T) -> T>,
+
+    /// What happens when pushing into a full queue.
+    overflow_policy: OverflowPolicy,
+
+    /// The number of items that have been lost due to the overflow policy.
+    ///
+    /// This is only written by the [`Producer`] and it wraps around on overflow.
+    overflowed: CachePadded<AtomicUsize>,
+
+    /// One stamp per slot, used by the [`HistoryReader`] to detect overwritten items.
+    ///
+    /// While the [`Producer`] is writing the item with sequence number `seq`,
+    /// the stamp is `2 * seq + 1`, afterwards it is `2 * seq + 2` (both wrapping around).
+    /// This is empty unless the ring buffer was created with
+    /// [`RingBuffer::with_history_reader()`].
+    stamps: Vec<AtomicUsize>,
+
+    /// Set while the [`HistoryReader`] exists.
+    ///
+    /// It reads unread items as well, so the [`Consumer`] must not modify them
+    /// (see [`ReadChunk::as_mut_slices()`](chunks::ReadChunk::as_mut_slices)).
+    history_reader: AtomicBool,
+
+    /// Set when the [`Producer`] or the [`Consumer`] is dropped.
+    abandoned: AtomicBool,
+
+    /// Parking word for a [`Producer`] waiting in [`Producer::push_blocking()`].
+    #[cfg(feature = "std")]
+    producer_waiter: CachePadded<blocking::Waiter>,
+
+    /// Parking word for a [`Consumer`] waiting in [`Consumer::pop_blocking()`]
+    /// or [`Consumer::read_chunk_blocking()`].
+    #[cfg(feature = "std")]
+    consumer_waiter: CachePadded<blocking::Waiter>,
+
+    /// The task of a [`Producer`] waiting in [`Sink::poll_ready()`](futures_sink::Sink).
+    #[cfg(feature = "async")]
+    producer_waker: CachePadded<atomic_waker::AtomicWaker>,
+
+    /// The task of a [`Consumer`] waiting in [`Stream::poll_next()`](futures_core::Stream).
+    #[cfg(feature = "async")]
+    consumer_waker: CachePadded<atomic_waker::AtomicWaker>,
+
+    /// Enabled with [`Producer::enable_eventfd()`].
+    #[cfg(all(feature = "eventfd", target_os = "linux"))]
+    producer_eventfd: CachePadded<eventfd::EventFd>,
+
+    /// Enabled with [`Consumer::enable_eventfd()`].
+    #[cfg(all(feature = "eventfd", target_os = "linux"))]
+    consumer_eventfd: CachePadded<eventfd::EventFd>,
 
     /// Indicates that dropping a `RingBuffer<T>` may drop elements of type `T`.
     _marker: PhantomData<T>,
@@ -108,7 +225,7 @@ impl<T> RingBuffer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (producer, consumer) = RingBuffer::<f32>::new(100);
+    /// let (producer, consumer) = RingBuffer::<f32>::new(100, 0);
     /// ```
     ///
     /// Specifying an explicit type with the [turbofish](https://turbo.fish/)
@@ -117,31 +234,284 @@ impl<T> RingBuffer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (mut producer, consumer) = RingBuffer::new(100);
-    /// assert_eq!(producer.push(0.0f32), Ok(()));
+    /// let (mut producer, consumer) = RingBuffer::new(100, 0);
+    /// assert_eq!(producer.push(0.0f32), Ok(0));
+    /// ```
+    ///
+    /// The `resend_window` specifies how many consumed slots are protected
+    /// from being overwritten by the [`Producer`].
+    /// Only items that stay in the ring buffer can be retained,
+    /// i.e. the ones consumed with
+    /// [`ReadChunk::commit_retaining()`](chunks::ReadChunk::commit_retaining).
+    /// [`Consumer::pop()`] moves the item out, which releases all retained items,
+    /// so there is nothing left to re-deliver with [`Consumer::rewind()`].
+    /// To retain popped items, use [`RingBuffer::with_replay()`] instead
+    /// (which requires `T: Clone`).
+    ///
     /// ```
+    /// use rtrb::{RewindError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::new(4, 2);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// c.read_chunk(1).unwrap().commit_retaining(1);
+    /// assert_eq!(c.retained(), 1);
+    /// assert_eq!(c.pop(), Ok(20));
+    /// assert_eq!(c.retained(), 0);
+    /// assert_eq!(c.rewind(1), Err(RewindError::TooFewItems(0)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resend_window` is greater than `capacity`.
     #[allow(clippy::new_ret_no_self)]
     #[must_use]
     pub fn new(capacity: usize, resend_window: usize) -> (Producer<T>, Consumer<T>) {
-        assert!(resend_window <= capacity, "Resend window cannot exceed capacity");
-        
-        let buffer = Arc::new(RingBuffer {
+        Self::create(capacity, resend_window, None, false, OverflowPolicy::Block).into_pair()
+    }
+
+    /// Creates a `RingBuffer` that keeps the last `resend_window` consumed items for replay.
+    ///
+    /// [`Consumer::pop()`] hands out a clone of each item,
+    /// the original stays in the ring buffer until it falls out of the resend window.
+    /// Retained items can be re-delivered with [`Consumer::rewind()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(c.pop(), Ok(20));
+    /// assert_eq!(c.retained(), 2);
+    /// assert_eq!(c.rewind(1), Ok(()));
+    /// assert_eq!(c.pop(), Ok(20));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resend_window` is greater than `capacity`.
+    #[must_use]
+    pub fn with_replay(capacity: usize, resend_window: usize) -> (Producer<T>, Consumer<T>)
+    where
+        T: Clone,
+    {
+        Self::create(
+            capacity,
+            resend_window,
+            Some(T::clone),
+            false,
+            OverflowPolicy::Block,
+        )
+        .into_pair()
+    }
+
+    /// Creates a `RingBuffer` that keeps consumed items until they are acknowledged.
+    ///
+    /// Instead of a fixed resend window, all items that have been read
+    /// but not yet acknowledged with [`Consumer::ack()`] are retained
+    /// and can be re-delivered with [`Consumer::rewind()`].
+    /// The [`Producer`] cannot overwrite unacknowledged items,
+    /// which makes it possible to implement at-least-once delivery.
+    ///
+    /// [`Consumer::pop()`] hands out a clone of each item, like with [`RingBuffer::with_replay()`].
+    /// Committing a [`ReadChunk`](chunks::ReadChunk) acknowledges all items up to the end
+    /// of the committed slots, unless
+    /// [`ReadChunk::commit_retaining()`](chunks::ReadChunk::commit_retaining) is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{PushError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_acks(2);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(p.push(30), Err(PushError::WindowFull(30)));
+    /// assert_eq!(c.ack(0), Ok(()));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// ```
+    #[must_use]
+    pub fn with_acks(capacity: usize) -> (Producer<T>, Consumer<T>)
+    where
+        T: Clone,
+    {
+        Self::create(
+            capacity,
+            capacity,
+            Some(T::clone),
+            true,
+            OverflowPolicy::Block,
+        )
+        .into_pair()
+    }
+
+    /// Creates a `RingBuffer` that handles [`Producer::push()`] into a full queue
+    /// according to the given [`OverflowPolicy`].
+    ///
+    /// Apart from that, this behaves like [`RingBuffer::new()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{OverflowPolicy, PopError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::OverwriteOldest);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// assert_eq!(c.pop(), Err(PopError::Lagged { first: 0, skipped: 1 }));
+    /// assert_eq!(c.pop_with_seq(), Ok((1, 20)));
+    /// assert_eq!(c.pop_with_seq(), Ok((2, 30)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resend_window` is greater than `capacity`.
+    /// With [`OverflowPolicy::OverwriteOldest`], it also panics if `resend_window` is non-zero.
+    #[must_use]
+    pub fn with_overflow_policy(
+        capacity: usize,
+        resend_window: usize,
+        overflow_policy: OverflowPolicy,
+    ) -> (Producer<T>, Consumer<T>) {
+        Self::create(capacity, resend_window, None, false, overflow_policy).into_pair()
+    }
+
+    /// Creates a `RingBuffer` that can be inspected from a third thread
+    /// and returns [`Producer`], [`Consumer`] and [`HistoryReader`].
+    ///
+    /// The [`HistoryReader`] can read the retained and pending items
+    /// while the [`Producer`] and the [`Consumer`] keep running.
+    /// Items are retained like with [`RingBuffer::with_replay()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c, reader) = RingBuffer::with_history_reader(4, 1);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(c.pop(), Ok(10));
+    ///
+    /// std::thread::spawn(move || {
+    ///     let snapshot = reader.snapshot();
+    ///     assert_eq!(snapshot.start_index(), 0);
+    ///     assert_eq!(snapshot.as_slice(), [10, 20]);
+    /// }).join().unwrap();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resend_window` is greater than `capacity`.
+    #[must_use]
+    pub fn with_history_reader(
+        capacity: usize,
+        resend_window: usize,
+    ) -> (Producer<T>, Consumer<T>, HistoryReader<T>)
+    where
+        T: Copy,
+    {
+        let mut buffer = Self::create(
+            capacity,
+            resend_window,
+            Some(T::clone),
+            false,
+            OverflowPolicy::Block,
+        );
+        buffer.stamps = (0..capacity).map(|_| AtomicUsize::new(0)).collect();
+        buffer.history_reader = AtomicBool::new(true);
+        let buffer = Arc::new(buffer);
+        let reader = HistoryReader {
+            buffer: buffer.clone(),
+            last_seq: Cell::new(0),
+        };
+        let (p, c) = Self::split(buffer);
+        (p, c, reader)
+    }
+
+    fn create(
+        capacity: usize,
+        resend_window: usize,
+        clone_fn: Option<fn(&T) -> T>,
+        ack_required: bool,
+        overflow_policy: OverflowPolicy,
+    ) -> RingBuffer<T> {
+        assert!(
+            resend_window <= capacity,
+            "Resend window cannot exceed capacity"
+        );
+        if overflow_policy == OverflowPolicy::OverwriteOldest {
+            assert!(
+                resend_window == 0,
+                "Resend window is not supported when overwriting the oldest items"
+            );
+            // All positions must fit next to the PINNED bit.
+            assert!(capacity <= PINNED / 2, "Capacity is too large");
+        }
+
+        RingBuffer {
             head: CachePadded::new(AtomicUsize::new(0)),
             tail: CachePadded::new(AtomicUsize::new(0)),
+            release: CachePadded::new(AtomicUsize::new(0)),
             data_ptr: ManuallyDrop::new(Vec::with_capacity(capacity)).as_mut_ptr(),
             capacity,
-            resend_window,
+            resend_window: AtomicUsize::new(resend_window),
+            ack_required,
+            clone_fn,
+            overflow_policy,
+            overflowed: CachePadded::new(AtomicUsize::new(0)),
+            stamps: Vec::new(),
+            history_reader: AtomicBool::new(false),
+            abandoned: AtomicBool::new(false),
+            #[cfg(feature = "std")]
+            producer_waiter: CachePadded::new(blocking::Waiter::new()),
+            #[cfg(feature = "std")]
+            consumer_waiter: CachePadded::new(blocking::Waiter::new()),
+            #[cfg(feature = "async")]
+            producer_waker: CachePadded::new(atomic_waker::AtomicWaker::new()),
+            #[cfg(feature = "async")]
+            consumer_waker: CachePadded::new(atomic_waker::AtomicWaker::new()),
+            #[cfg(all(feature = "eventfd", target_os = "linux"))]
+            producer_eventfd: CachePadded::new(eventfd::EventFd::new()),
+            #[cfg(all(feature = "eventfd", target_os = "linux"))]
+            consumer_eventfd: CachePadded::new(eventfd::EventFd::new()),
             _marker: PhantomData,
-        });
+        }
+    }
+
+    /// Moves the ring buffer to the heap and returns [`Producer`] and [`Consumer`].
+    fn into_pair(self) -> (Producer<T>, Consumer<T>) {
+        Self::split(Arc::new(self))
+    }
+
+    /// Returns [`Producer`] and [`Consumer`] for a ring buffer that is already on the heap.
+    fn split(buffer: Arc<RingBuffer<T>>) -> (Producer<T>, Consumer<T>) {
+        let capacity = buffer.capacity;
         let p = Producer {
             buffer: buffer.clone(),
             cached_head: Cell::new(0),
             cached_tail: Cell::new(0),
+            tail_seq: Cell::new(0),
         };
+        let sacked_words = (capacity + USIZE_BITS - 1) / USIZE_BITS;
         let c = Consumer {
             buffer,
             cached_head: Cell::new(0),
             cached_tail: Cell::new(0),
+            head_seq: Cell::new(0),
+            rewound_seq: Cell::new(0),
+            sacked: (0..sacked_words).map(|_| Cell::new(0)).collect(),
+            pinned: Cell::new(false),
+            lagged: Cell::new(0),
+            lagged_seq: Cell::new(0),
+            lost: Cell::new(0),
+            overflow_seen: Cell::new(0),
         };
         (p, c)
     }
@@ -153,7 +523,7 @@ impl<T> RingBuffer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (producer, consumer) = RingBuffer::<f32>::new(100);
+    /// let (producer, consumer) = RingBuffer::<f32>::new(100, 0);
     /// assert_eq!(producer.buffer().capacity(), 100);
     /// assert_eq!(consumer.buffer().capacity(), 100);
     /// // Both producer and consumer of course refer to the same ring buffer:
@@ -164,8 +534,38 @@ impl<T> RingBuffer<T> {
     }
 
     /// Returns the resend window size of the queue.
+    ///
+    /// If the ring buffer was created with [`RingBuffer::with_acks()`],
+    /// this is the same as the capacity.
+    ///
+    /// The resend window can be changed with [`Consumer::set_resend_window()`].
     pub fn resend_window(&self) -> usize {
-        self.resend_window
+        self.resend_window.load(Ordering::Relaxed)
+    }
+
+    /// Returns the overflow policy of the queue, see [`RingBuffer::with_overflow_policy()`].
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// Returns the number of items that have been lost due to the overflow policy.
+    ///
+    /// These are the items that have been overwritten with [`OverflowPolicy::OverwriteOldest`]
+    /// or dropped with [`OverflowPolicy::DropNewest`].
+    /// The number wraps around on overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{OverflowPolicy, PushError, RingBuffer};
+    ///
+    /// let (mut p, c) = RingBuffer::with_overflow_policy(1, 0, OverflowPolicy::DropNewest);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Err(PushError::Dropped));
+    /// assert_eq!(c.buffer().overflowed(), 1);
+    /// ```
+    pub fn overflowed(&self) -> usize {
+        self.overflowed.load(Ordering::Acquire)
     }
 
     /// Wraps a position from the range `0 .. 2 * capacity` to `0 .. capacity`.
@@ -213,6 +613,17 @@ impl<T> RingBuffer<T> {
         }
     }
 
+    /// Decrements a position by going `n` slots backward.
+    fn decrement(&self, pos: usize, n: usize) -> usize {
+        debug_assert!(pos == 0 || pos < 2 * self.capacity);
+        debug_assert!(n <= self.capacity);
+        if pos >= n {
+            pos - n
+        } else {
+            2 * self.capacity - n + pos
+        }
+    }
+
     /// Returns the distance between two positions.
     fn distance(&self, a: usize, b: usize) -> usize {
         debug_assert!(a == 0 || a < 2 * self.capacity);
@@ -223,12 +634,101 @@ impl<T> RingBuffer<T> {
             2 * self.capacity - a + b
         }
     }
+
+    /// Returns the number of unread items, as seen from any thread.
+    #[cfg(feature = "std")]
+    fn readable(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire) & !PINNED;
+        self.distance(head, self.tail.load(Ordering::Acquire))
+    }
+
+    /// Wakes a waiting [`Consumer`] if enough items are available.
+    ///
+    /// This has to be called after storing `tail`.
+    fn notify_consumer(&self) {
+        #[cfg(feature = "std")]
+        self.consumer_waiter.notify(|| self.readable());
+        #[cfg(feature = "async")]
+        self.consumer_waker.wake();
+        #[cfg(all(feature = "eventfd", target_os = "linux"))]
+        self.consumer_eventfd.signal();
+    }
+
+    /// Wakes a waiting [`Producer`].
+    ///
+    /// This has to be called after storing `head` or `release`.
+    fn notify_producer(&self) {
+        #[cfg(feature = "std")]
+        self.producer_waiter.notify(|| 1);
+        #[cfg(feature = "async")]
+        self.producer_waker.wake();
+        #[cfg(all(feature = "eventfd", target_os = "linux"))]
+        self.producer_eventfd.signal();
+    }
+
+    /// Wakes both sides, regardless of the available items.
+    fn notify_abandoned(&self) {
+        #[cfg(feature = "std")]
+        {
+            self.consumer_waiter.notify(|| !0);
+            self.producer_waiter.notify(|| !0);
+        }
+        #[cfg(feature = "async")]
+        {
+            self.consumer_waker.wake();
+            self.producer_waker.wake();
+        }
+        #[cfg(all(feature = "eventfd", target_os = "linux"))]
+        {
+            self.consumer_eventfd.signal();
+            self.producer_eventfd.signal();
+        }
+    }
+
+    /// Marks `n` slots starting at position `pos` as being written,
+    /// starting with sequence number `seq`.
+    ///
+    /// This has to be called before writing to the slots,
+    /// it does nothing if there is no [`HistoryReader`].
+    fn begin_writes(&self, pos: usize, seq: u64, n: usize) {
+        if self.stamps.is_empty() {
+            return;
+        }
+        let mut pos = self.collapse_position(pos);
+        for i in 0..n {
+            self.stamps[pos].store(stamp(seq + i as u64) - 1, Ordering::Relaxed);
+            pos = if pos + 1 < self.capacity { pos + 1 } else { 0 };
+        }
+        fence(Ordering::Release);
+    }
+
+    /// Marks `n` slots starting at position `pos` as completely written,
+    /// see `begin_writes()`.
+    fn end_writes(&self, pos: usize, seq: u64, n: usize) {
+        if self.stamps.is_empty() {
+            return;
+        }
+        let mut pos = self.collapse_position(pos);
+        for i in 0..n {
+            self.stamps[pos].store(stamp(seq + i as u64), Ordering::Release);
+            pos = if pos + 1 < self.capacity { pos + 1 } else { 0 };
+        }
+    }
+}
+
+/// Returns the stamp of a slot that has been completely written with sequence number `seq`.
+fn stamp(seq: u64) -> usize {
+    (seq as usize).wrapping_mul(2).wrapping_add(2)
 }
 
 impl<T> Drop for RingBuffer<T> {
-    /// Drops all non-empty slots.
+    /// Drops all non-empty slots, including the retained ones.
     fn drop(&mut self) {
-        let mut head = self.head.load(Ordering::Relaxed);
+        let mut head = if self.resend_window() == 0 {
+            self.head.load(Ordering::Relaxed) & !PINNED
+        } else {
+            self.release.load(Ordering::Relaxed)
+        };
         let tail = self.tail.load(Ordering::Relaxed);
 
         // Loop over all slots that hold a value and drop them.
@@ -252,10 +752,10 @@ impl<T> PartialEq for RingBuffer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (p1, c1) = RingBuffer::<f32>::new(1000);
+    /// let (p1, c1) = RingBuffer::<f32>::new(1000, 0);
     /// assert_eq!(p1.buffer(), c1.buffer());
     ///
-    /// let (p2, c2) = RingBuffer::<f32>::new(1000);
+    /// let (p2, c2) = RingBuffer::<f32>::new(1000, 0);
     /// assert_ne!(p1.buffer(), p2.buffer());
     /// ```
     fn eq(&self, other: &Self) -> bool {
@@ -265,6 +765,48 @@ impl<T> PartialEq for RingBuffer<T> {
 
 impl<T> Eq for RingBuffer<T> {}
 
+/// Specifies what happens when [`Producer::push()`] is called on a full queue.
+///
+/// This can be chosen with [`RingBuffer::with_overflow_policy()`].
+/// Writing chunks is not affected, [`Producer::write_chunk_uninit()`] always fails
+/// if there are not enough free slots.
+///
+/// Pushing and popping stays wait-free with all policies.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// The element is returned back in [`PushError::Full`] (or [`PushError::WindowFull`]).
+    ///
+    /// This is the behavior of [`RingBuffer::new()`].
+    Block,
+    /// The oldest unread item is dropped to make space for the new element.
+    ///
+    /// The [`Consumer`] learns about the lost items through [`PopError::Lagged`]
+    /// (or [`ChunkError::Lagged`](chunks::ChunkError::Lagged)),
+    /// see also [`Consumer::lost_total()`].
+    ///
+    /// While the [`Consumer`] is accessing unread items with [`Consumer::read_chunk()`]
+    /// or [`Consumer::begin()`], they are not overwritten
+    /// and [`PushError::Full`] is returned instead.
+    /// They are protected until the chunk is committed or the transaction ends
+    /// (or until the next item is consumed if a chunk is dropped without committing).
+    /// [`Consumer::peek()`] and [`Consumer::history()`] don't provide access to unread items
+    /// with this policy.
+    ///
+    /// A resend window is not supported with this policy.
+    OverwriteOldest,
+    /// The new element is dropped and [`PushError::Dropped`] is returned.
+    ///
+    /// The dropped items don't get a sequence number,
+    /// they are only counted in [`RingBuffer::overflowed()`].
+    DropNewest,
+}
+
+impl Default for OverflowPolicy {
+    fn default() -> Self {
+        OverflowPolicy::Block
+    }
+}
+
 /// The producer side of a [`RingBuffer`].
 ///
 /// Can be moved between threads,
@@ -293,6 +835,8 @@ pub struct Producer<T> {
 
     /// A copy of `buffer.head` for quick access.
     ///
+    /// If acknowledgements are required, this is a copy of `buffer.release` instead.
+    ///
     /// This value can be stale and sometimes needs to be resynchronized with `buffer.head`.
     cached_head: Cell<usize>,
 
@@ -302,49 +846,80 @@ pub struct Producer<T> {
     // NB: Caching the tail seems to have little effect on Intel CPUs, but it seems to
     //     improve performance on AMD CPUs, see https://github.com/mgeier/rtrb/pull/132
     cached_tail: Cell<usize>,
+
+    /// The sequence number of the next item to be written.
+    ///
+    /// Unlike `cached_tail`, this never wraps around.
+    tail_seq: Cell<u64>,
 }
 
 // SAFETY: After moving a Producer to another thread, there is still only a single thread
 // that can access the producer side of the queue.
 unsafe impl<T: Send> Send for Producer<T> {}
 
+impl<T> Drop for Producer<T> {
+    /// Makes [`Consumer::is_abandoned()`] return `true`.
+    fn drop(&mut self) {
+        self.buffer.abandoned.store(true, Ordering::Release);
+        self.buffer.notify_abandoned();
+    }
+}
+
 impl<T> Producer<T> {
     /// Attempts to push an element into the queue.
     ///
     /// The element is *moved* into the ring buffer and its slot
     /// is made available to be read by the [`Consumer`].
     ///
+    /// On success, the sequence number of the element is returned.
+    /// The first element ever written has the sequence number `0`,
+    /// every following element (including the ones written with chunks)
+    /// gets the next higher number.
+    ///
     /// # Errors
     ///
     /// If the queue is full, the element is returned back as an error.
+    /// If there would be an empty slot, but it is protected by the resend window,
+    /// [`PushError::WindowFull`] is returned instead of [`PushError::Full`].
+    ///
+    /// If the ring buffer has been created with [`RingBuffer::with_overflow_policy()`],
+    /// a full queue is handled as described in [`OverflowPolicy`].
     ///
     /// # Examples
     ///
     /// ```
     /// use rtrb::{RingBuffer, PushError};
     ///
-    /// let (mut p, c) = RingBuffer::new(1);
+    /// let (mut p, c) = RingBuffer::new(1, 0);
     ///
-    /// assert_eq!(p.push(10), Ok(()));
+    /// assert_eq!(p.push(10), Ok(0));
     /// assert_eq!(p.push(20), Err(PushError::Full(20)));
     /// ```
-    pub fn push(&mut self, value: T) -> Result<(), PushError<T>> {
+    pub fn push(&mut self, value: T) -> Result<u64, PushError<T>> {
         if let Some(tail) = self.next_tail() {
+            let seq = self.tail_seq.get();
+            self.buffer.begin_writes(tail, seq, 1);
             // SAFETY: tail points to an empty slot.
             unsafe { self.buffer.slot_ptr(tail).write(value) };
+            self.buffer.end_writes(tail, seq, 1);
             let tail = self.buffer.increment1(tail);
             self.buffer.tail.store(tail, Ordering::Release);
+            self.buffer.notify_consumer();
             self.cached_tail.set(tail);
-            Ok(())
+            self.tail_seq.set(seq + 1);
+            Ok(seq)
         } else {
-            Err(PushError::Full(value))
+            self.overflow(value)
         }
     }
 
     /// Returns the number of slots available for writing.
     ///
+    /// Slots that are protected by the resend window (see [`RingBuffer::new()`])
+    /// are not available for writing and therefore not counted.
+    ///
     /// Since items can be concurrently consumed on another thread, the actual number
-    /// of available slots may increase at any time (up to the [`RingBuffer::capacity()`]).
+    /// of available slots may increase at any time (up to [`Producer::max_advance()`]).
     ///
     /// To check for a single available slot,
     /// using [`Producer::is_full()`] is often quicker
@@ -355,14 +930,13 @@ impl<T> Producer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (p, c) = RingBuffer::<f32>::new(1024);
+    /// let (p, c) = RingBuffer::<f32>::new(1024, 0);
     ///
     /// assert_eq!(p.slots(), 1024);
     /// ```
     pub fn slots(&self) -> usize {
-        let head = self.buffer.head.load(Ordering::Acquire);
-        self.cached_head.set(head);
-        self.buffer.capacity - self.buffer.distance(head, self.cached_tail.get())
+        let head = self.refresh_head();
+        self.usable_slots(head)
     }
 
     /// Returns `true` if there are currently no slots available for writing.
@@ -375,7 +949,7 @@ impl<T> Producer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (p, c) = RingBuffer::<f32>::new(1);
+    /// let (p, c) = RingBuffer::<f32>::new(1, 0);
     ///
     /// assert!(!p.is_full());
     /// ```
@@ -385,7 +959,7 @@ impl<T> Producer<T> {
     ///
     /// ```
     /// # use rtrb::RingBuffer;
-    /// # let (p, c) = RingBuffer::<f32>::new(1);
+    /// # let (p, c) = RingBuffer::<f32>::new(1, 0);
     /// if p.is_full() {
     ///     // The buffer might be full, but it might as well not be
     ///     // if an item was just consumed on another thread.
@@ -396,7 +970,7 @@ impl<T> Producer<T> {
     ///
     /// ```
     /// # use rtrb::RingBuffer;
-    /// # let (p, c) = RingBuffer::<f32>::new(1);
+    /// # let (p, c) = RingBuffer::<f32>::new(1, 0);
     /// if !p.is_full() {
     ///     // At least one slot is guaranteed to be available for writing.
     /// }
@@ -416,14 +990,14 @@ impl<T> Producer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (mut p, c) = RingBuffer::new(7);
+    /// let (mut p, c) = RingBuffer::new(7, 0);
     /// assert!(!p.is_abandoned());
-    /// assert_eq!(p.push(10), Ok(()));
+    /// assert_eq!(p.push(10), Ok(0));
     /// drop(c);
     /// // The items that are still in the ring buffer are not accessible anymore.
     /// assert!(p.is_abandoned());
     /// // Even though it's futile, items can still be written:
-    /// assert_eq!(p.push(11), Ok(()));
+    /// assert_eq!(p.push(11), Ok(1));
     /// ```
     ///
     /// Since the consumer can be concurrently dropped on another thread,
@@ -431,7 +1005,7 @@ impl<T> Producer<T> {
     ///
     /// ```
     /// # use rtrb::RingBuffer;
-    /// # let (p, c) = RingBuffer::<i32>::new(1);
+    /// # let (p, c) = RingBuffer::<i32>::new(1, 0);
     /// if !p.is_abandoned() {
     ///     // Right now, the consumer might still be alive, but it might as well not be
     ///     // if another thread has just dropped it.
@@ -442,7 +1016,7 @@ impl<T> Producer<T> {
     ///
     /// ```
     /// # use rtrb::RingBuffer;
-    /// # let (p, c) = RingBuffer::<i32>::new(1);
+    /// # let (p, c) = RingBuffer::<i32>::new(1, 0);
     /// if p.is_abandoned() {
     ///     // This is needed since Rust 1.74.0, see https://github.com/mgeier/rtrb/issues/114:
     ///     std::sync::atomic::fence(std::sync::atomic::Ordering::Acquire);
@@ -450,7 +1024,7 @@ impl<T> Producer<T> {
     /// }
     /// ```
     pub fn is_abandoned(&self) -> bool {
-        Arc::strong_count(&self.buffer) < 2
+        self.buffer.abandoned.load(Ordering::Relaxed)
     }
 
     /// Returns a read-only reference to the ring buffer.
@@ -458,9 +1032,77 @@ impl<T> Producer<T> {
         &self.buffer
     }
 
+    /// Get read-only access to the items that have been read but not released yet
+    ///
+    /// The window contains the items that are retained by the [`Consumer`]
+    /// (see [`Consumer::rewind()`] and [`Consumer::ack()`]).
+    /// This can be used to resend items from the producer thread.
+    /// Items that have not been read yet are not available,
+    /// because the [`Consumer`] might modify them with
+    /// [`ReadChunk::as_mut_slices()`](chunks::ReadChunk::as_mut_slices).
+    ///
+    /// The [`Consumer`] may concurrently read, rewind and release items.
+    /// This is only sound because items of a [`Copy`] type are never modified
+    /// (or dropped) by reading and releasing them, items that are delivered again
+    /// after rewinding cannot be accessed mutably,
+    /// and the `Producer` cannot overwrite them while the window exists.
+    /// Items released after obtaining the window are still accessible through it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// assert_eq!(c.pop(), Ok(20));
+    /// assert_eq!(c.pop(), Ok(30));
+    ///
+    /// assert_eq!(p.push(40), Ok(3));
+    ///
+    /// let history = p.history();
+    /// assert_eq!(history.start_index(), 1);
+    /// assert_eq!(history.get(2), Ok(&30));
+    /// assert_eq!(history.iter().collect::<Vec<_>>(), [&20, &30]);
+    /// ```
+    pub fn history(&self) -> HistoryWindow<'_, T>
+    where
+        T: Copy,
+    {
+        // NB: If the resend window was zero before, `release` is updated before this is stored.
+        let start = if self.buffer.resend_window.load(Ordering::Acquire) == 0 {
+            None
+        } else {
+            Some(self.buffer.release.load(Ordering::Acquire))
+        };
+        // `head` never moves before `release`, so this has to be loaded afterwards.
+        let head = self.buffer.head.load(Ordering::Acquire) & !PINNED;
+        let start = start.unwrap_or(head);
+        let tail = self.cached_tail.get();
+        HistoryWindow {
+            buffer: &self.buffer,
+            start,
+            start_seq: self.tail_seq.get() - self.buffer.distance(start, tail) as u64,
+            length: self.buffer.distance(start, head),
+            _marker: PhantomData,
+        }
+    }
+
     /// Get maximum allowed advance from given position
+    ///
+    /// If the ring buffer was created with [`RingBuffer::with_acks()`],
+    /// the position is the one of the oldest unacknowledged item
+    /// and the whole capacity is available.
     pub fn max_advance(&self) -> usize {
-        self.buffer.capacity - self.buffer.resend_window
+        if self.buffer.ack_required {
+            self.buffer.capacity
+        } else {
+            // NB: This has to be loaded after `head`, see Consumer::set_resend_window().
+            self.buffer.capacity - self.buffer.resend_window.load(Ordering::Acquire)
+        }
     }
 
     /// Get the tail position for writing the next slot, if available.
@@ -468,20 +1110,142 @@ impl<T> Producer<T> {
     /// This is a strict subset of the functionality implemented in `write_chunk_uninit()`.
     /// For performance, this special case is immplemented separately.
     fn next_tail(&self) -> Option<usize> {
-        let tail = self.cached_tail.get();
-
         // Fast-path check with cached head
-        if self.buffer.distance(self.cached_head.get(), tail) >= self.max_advance() {
+        if self.usable_slots(self.cached_head.get()) == 0 {
             // Re-check with updated head
-            let head = self.buffer.head.load(Ordering::Acquire);
-            self.cached_head.set(head);
-            
-            if self.buffer.distance(head, tail) >= self.max_advance() {
+            let head = self.refresh_head();
+
+            if self.usable_slots(head) == 0 {
                 //Block
                 return None;
             }
         }
-        Some(tail)
+        Some(self.cached_tail.get())
+    }
+
+    /// Loads the position that must not be overtaken and stores it in `cached_head`.
+    ///
+    /// This is `head`, unless acknowledgements are required.
+    fn refresh_head(&self) -> usize {
+        let head = if self.buffer.ack_required {
+            self.buffer.release.load(Ordering::Acquire)
+        } else {
+            self.buffer.head.load(Ordering::Acquire) & !PINNED
+        };
+        self.cached_head.set(head);
+        head
+    }
+
+    /// Handles pushing into a full queue, according to the overflow policy.
+    #[cold]
+    fn overflow(&mut self, value: T) -> Result<u64, PushError<T>> {
+        match self.buffer.overflow_policy {
+            OverflowPolicy::Block => {}
+            OverflowPolicy::OverwriteOldest => {
+                if self.overwrite_oldest() {
+                    return self.push(value);
+                }
+            }
+            OverflowPolicy::DropNewest => {
+                drop(value);
+                self.count_overflow();
+                return Err(PushError::Dropped);
+            }
+        }
+        if self.occupancy().1 == 0 {
+            Err(PushError::Full(value))
+        } else {
+            Err(PushError::WindowFull(value))
+        }
+    }
+
+    /// Drops the oldest unread item to make space for a new one.
+    ///
+    /// Returns `false` if the [`Consumer`] is currently accessing unread items.
+    /// This relies on `cached_head` having just been refreshed.
+    fn overwrite_oldest(&self) -> bool {
+        let head = self.cached_head.get();
+        if head == self.cached_tail.get() {
+            return false;
+        }
+        let next = self.buffer.increment1(head);
+        // NB: This fails if the consumer has set the PINNED bit.
+        match self
+            .buffer
+            .head
+            .compare_exchange(head, next, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => {}
+            Err(actual) if actual & PINNED == 0 => {
+                // The consumer has made space in the meantime.
+                self.cached_head.set(actual);
+                return true;
+            }
+            Err(_) => return false,
+        }
+        // SAFETY: The slot at head is initialized and the consumer has given up on it.
+        unsafe { self.buffer.slot_ptr(head).drop_in_place() };
+        self.cached_head.set(next);
+        self.count_overflow();
+        true
+    }
+
+    /// Increments the number of items lost due to the overflow policy.
+    ///
+    /// When overwriting, this must happen after moving `head`, see `Consumer::pin()`.
+    fn count_overflow(&self) {
+        let overflowed = &self.buffer.overflowed;
+        let n = overflowed.load(Ordering::Relaxed).wrapping_add(1);
+        overflowed.store(n, Ordering::Release);
+    }
+
+    /// Returns the number of retained items and the number of slots
+    /// that are not blocked by them.
+    ///
+    /// Without acknowledgements, the latter are the unoccupied slots.
+    /// With acknowledgements, it includes the slots of unacknowledged items.
+    ///
+    /// This is used to find out why writing has failed, it relies on `cached_head`
+    /// having just been refreshed.
+    fn occupancy(&self) -> (usize, usize) {
+        if self.buffer.ack_required {
+            let head = self.buffer.head.load(Ordering::Relaxed);
+            let retained = self.buffer.distance(self.cached_head.get(), head);
+            // When committing chunks, `release` is updated before `head`.
+            let retained = if retained <= self.buffer.capacity {
+                retained
+            } else {
+                0
+            };
+            let pending = self.buffer.distance(head, self.cached_tail.get());
+            return (retained, self.buffer.capacity.saturating_sub(pending));
+        }
+        let head = self.cached_head.get();
+        let retained = if self.buffer.resend_window() == 0 {
+            0
+        } else {
+            let release = self.buffer.release.load(Ordering::Relaxed);
+            let retained = self.buffer.distance(release, head);
+            // If `release` is newer than `head`, the distance wraps around.
+            if retained <= self.buffer.resend_window() {
+                retained
+            } else {
+                0
+            }
+        };
+        let pending = self.buffer.distance(head, self.cached_tail.get());
+        let unoccupied = self.buffer.capacity.saturating_sub(pending + retained);
+        (retained, unoccupied)
+    }
+
+    /// Returns the number of slots that can be written, given the position of the head.
+    ///
+    /// All producer-side write operations use this to stay out of the resend window.
+    /// After [`Consumer::rewind()`], the distance between `head` and `tail`
+    /// can temporarily exceed [`Producer::max_advance()`].
+    fn usable_slots(&self, head: usize) -> usize {
+        let distance = self.buffer.distance(head, self.cached_tail.get());
+        self.max_advance().saturating_sub(distance)
     }
 }
 
@@ -497,6 +1261,12 @@ impl<T> Producer<T> {
 /// Individual elements can be moved out of the ring buffer with [`Consumer::pop()`],
 /// multiple elements at once can be read with [`Consumer::read_chunk()`].
 ///
+/// If the ring buffer has been created with [`RingBuffer::with_replay()`],
+/// consumed elements are retained in the resend window
+/// and can be read again after calling [`Consumer::rewind()`].
+/// If it has been created with [`RingBuffer::with_acks()`],
+/// they are retained until they are acknowledged with [`Consumer::ack()`].
+///
 /// The number of slots currently available for reading can be obtained with
 /// [`Consumer::slots()`].
 ///
@@ -521,30 +1291,74 @@ pub struct Consumer<T> {
     ///
     /// This value can be stale and sometimes needs to be resynchronized with `buffer.tail`.
     cached_tail: Cell<usize>,
-}
 
-// SAFETY: After moving a Consumer to another thread, there is still only a single thread
-// that can access the consumer side of the queue.
-unsafe impl<T: Send> Send for Consumer<T> {}
+    /// The sequence number of the item at `cached_head`.
+    ///
+    /// Unlike `cached_head`, this never wraps around.
+    head_seq: Cell<u64>,
 
-impl<T> Consumer<T> {
-    /// Attempts to pop an element from the queue.
+    /// The sequence number `head_seq` had before the most recent [`Consumer::rewind()`].
     ///
-    /// The element is *moved* out of the ring buffer and its slot
+    /// Items before this have been read before, see [`ReadChunk::as_mut_slices()`].
+    rewound_seq: Cell<u64>,
+
+    /// A bitmap with one bit per slot, marking selectively acknowledged retained items.
+    ///
+    /// This is allocated on construction and never resized afterwards,
+    /// see [`Consumer::selective_ack()`].
+    sacked: Vec<Cell<usize>>,
+
+    /// Whether the PINNED bit is currently set in `buffer.head`.
+    pinned: Cell<bool>,
+
+    /// The number of overwritten items that have not yet been reported by [`Consumer::pop()`].
+    lagged: Cell<u64>,
+
+    /// The sequence number of the first overwritten item that has not yet been reported.
+    lagged_seq: Cell<u64>,
+
+    /// The number of overwritten items, see [`Consumer::lost_total()`].
+    lost: Cell<u64>,
+
+    /// The value of `buffer.overflowed` that has already been taken into account.
+    overflow_seen: Cell<usize>,
+}
+
+// SAFETY: After moving a Consumer to another thread, there is still only a single thread
+// that can access the consumer side of the queue.
+unsafe impl<T: Send> Send for Consumer<T> {}
+
+impl<T> Drop for Consumer<T> {
+    /// Makes [`Producer::is_abandoned()`] return `true`.
+    fn drop(&mut self) {
+        self.buffer.abandoned.store(true, Ordering::Release);
+        self.buffer.notify_abandoned();
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Attempts to pop an element from the queue.
+    ///
+    /// The element is *moved* out of the ring buffer and its slot
     /// is made available to be filled by the [`Producer`] again.
     ///
     /// # Errors
     ///
     /// If the queue is empty, an error is returned.
     ///
+    /// If the [`Producer`] has overwritten unread items
+    /// (see [`OverflowPolicy::OverwriteOldest`]), [`PopError::Lagged`] is returned once
+    /// (containing the range of lost sequence numbers)
+    /// and the following call returns the oldest remaining item.
+    ///
     /// # Examples
     ///
     /// ```
     /// use rtrb::{PopError, RingBuffer};
     ///
-    /// let (mut p, mut c) = RingBuffer::new(1);
+    /// let (mut p, mut c) = RingBuffer::new(1, 0);
     ///
-    /// assert_eq!(p.push(10), Ok(()));
+    /// assert_eq!(p.push(10), Ok(0));
     /// assert_eq!(c.pop(), Ok(10));
     /// assert_eq!(c.pop(), Err(PopError::Empty));
     /// ```
@@ -553,42 +1367,101 @@ impl<T> Consumer<T> {
     ///
     /// ```
     /// # use rtrb::RingBuffer;
-    /// # let (mut p, mut c) = RingBuffer::new(1);
-    /// assert_eq!(p.push(20), Ok(()));
+    /// # let (mut p, mut c) = RingBuffer::new(1, 0);
+    /// assert_eq!(p.push(20), Ok(0));
     /// assert_eq!(c.pop().ok(), Some(20));
     /// ```
+    ///
+    /// If the ring buffer has been created with [`RingBuffer::with_replay()`],
+    /// a clone of the element is returned and the original is retained
+    /// in the resend window, see [`Consumer::rewind()`].
+    /// Otherwise, nothing is retained and previously retained items are released,
+    /// even if there is a resend window (see [`RingBuffer::new()`]).
     pub fn pop(&mut self) -> Result<T, PopError> {
         if let Some(head) = self.next_head() {
-            // SAFETY: head points to an initialized slot.
-            let value = unsafe { self.buffer.slot_ptr(head).read() };
-            let head = self.buffer.increment1(head);
-            self.buffer.head.store(head, Ordering::Release);
-            self.cached_head.set(head);
+            if let Some((first, skipped)) = self.take_lagged() {
+                // Unpin, there is no reason to stop the producer from overwriting.
+                self.store_head(head);
+                return Err(PopError::Lagged { first, skipped });
+            }
+            let value = if self.buffer.resend_window() == 0 {
+                // SAFETY: head points to an initialized slot.
+                unsafe { self.buffer.slot_ptr(head).read() }
+            } else {
+                self.take_retaining(head)
+            };
+            self.store_head(self.buffer.increment1(head));
+            self.head_seq.set(self.head_seq.get() + 1);
             Ok(value)
         } else {
             Err(PopError::Empty)
         }
     }
 
+    /// Attempts to pop an element from the queue, along with its sequence number.
+    ///
+    /// This behaves like [`Consumer::pop()`],
+    /// the sequence number is the one returned from [`Producer::push()`].
+    /// Elements that are delivered again after [`Consumer::rewind()`]
+    /// keep their original sequence number.
+    ///
+    /// # Errors
+    ///
+    /// If the queue is empty, an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{PopError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(3, 1);
+    ///
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(c.pop_with_seq(), Ok((0, 10)));
+    /// assert_eq!(c.rewind(1), Ok(()));
+    /// assert_eq!(c.pop_with_seq(), Ok((0, 10)));
+    /// assert_eq!(c.pop_with_seq(), Ok((1, 20)));
+    /// assert_eq!(c.pop_with_seq(), Err(PopError::Empty));
+    /// ```
+    pub fn pop_with_seq(&mut self) -> Result<(u64, T), PopError> {
+        let seq = self.head_seq.get();
+        self.pop().map(|value| (seq, value))
+    }
+
     /// Attempts to read an element from the queue without removing it.
     ///
     /// # Errors
     ///
     /// If the queue is empty, an error is returned.
     ///
+    /// With [`OverflowPolicy::OverwriteOldest`], [`PeekError::Overwritable`] is returned
+    /// instead of the oldest item, because the [`Producer`] might overwrite it while
+    /// it is borrowed.
+    /// [`Consumer::read_chunk()`] can be used instead,
+    /// which protects the items from being overwritten.
+    ///
     /// # Examples
     ///
     /// ```
     /// use rtrb::{PeekError, RingBuffer};
     ///
-    /// let (mut p, c) = RingBuffer::new(1);
+    /// let (mut p, c) = RingBuffer::new(1, 0);
     ///
     /// assert_eq!(c.peek(), Err(PeekError::Empty));
-    /// assert_eq!(p.push(10), Ok(()));
+    /// assert_eq!(p.push(10), Ok(0));
     /// assert_eq!(c.peek(), Ok(&10));
     /// assert_eq!(c.peek(), Ok(&10));
     /// ```
     pub fn peek(&self) -> Result<&T, PeekError> {
+        if self.buffer.overflow_policy == OverflowPolicy::OverwriteOldest {
+            // Pinning would stop the producer from overwriting for an unknown amount of time.
+            return Err(if self.has_unread() {
+                PeekError::Overwritable
+            } else {
+                PeekError::Empty
+            });
+        }
         if let Some(head) = self.next_head() {
             // SAFETY: head points to an initialized slot.
             Ok(unsafe { &*self.buffer.slot_ptr(head) })
@@ -611,14 +1484,150 @@ impl<T> Consumer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (p, c) = RingBuffer::<f32>::new(1024);
+    /// let (p, c) = RingBuffer::<f32>::new(1024, 0);
     ///
     /// assert_eq!(c.slots(), 0);
     /// ```
     pub fn slots(&self) -> usize {
+        let head = self.unread_head();
         let tail = self.buffer.tail.load(Ordering::Acquire);
         self.cached_tail.set(tail);
-        self.buffer.distance(self.cached_head.get(), tail)
+        // The producer might have overwritten items after `head` has been loaded.
+        self.buffer.distance(head, tail).min(self.buffer.capacity)
+    }
+
+    /// Returns the number of consumed items that are retained in the resend window.
+    ///
+    /// Those can be read again after calling [`Consumer::rewind()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(3, 1);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(c.retained(), 0);
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(c.retained(), 1);
+    /// assert_eq!(c.pop(), Ok(20));
+    /// // Only a single item fits into the resend window:
+    /// assert_eq!(c.retained(), 1);
+    /// ```
+    pub fn retained(&self) -> usize {
+        if self.buffer.resend_window() == 0 {
+            return 0;
+        }
+        let release = self.buffer.release.load(Ordering::Relaxed);
+        self.buffer.distance(release, self.cached_head.get())
+    }
+
+    /// Moves the read position `n` slots back into the retained items.
+    ///
+    /// The most recent `n` retained items are delivered again by
+    /// [`Consumer::pop()`], [`Consumer::peek()`] and [`Consumer::read_chunk()`].
+    ///
+    /// # Errors
+    ///
+    /// If fewer than `n` items are retained, an error
+    /// (containing the number of retained items) is returned.
+    /// Use [`Consumer::retained()`] to obtain the number of retained items beforehand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{RewindError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(5, 2);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(c.pop(), Ok(20));
+    /// assert_eq!(c.rewind(3), Err(RewindError::TooFewItems(2)));
+    /// assert_eq!(c.rewind(2), Ok(()));
+    /// assert_eq!(c.slots(), 3);
+    /// assert_eq!(c.pop(), Ok(10));
+    /// ```
+    ///
+    /// Items that are delivered again cannot be accessed mutably
+    /// with [`ReadChunk::as_mut_slices()`](chunks::ReadChunk::as_mut_slices).
+    pub fn rewind(&mut self, n: usize) -> Result<(), RewindError> {
+        let retained = self.retained();
+        if retained < n {
+            return Err(RewindError::TooFewItems(retained));
+        }
+        if self.head_seq.get() > self.rewound_seq.get() {
+            self.rewound_seq.set(self.head_seq.get());
+        }
+        self.pin();
+        self.store_head(self.buffer.decrement(self.cached_head.get(), n));
+        self.head_seq.set(self.head_seq.get() - n as u64);
+        Ok(())
+    }
+
+    /// Changes the number of consumed slots that are protected from being overwritten.
+    ///
+    /// If the new resend window is smaller than the number of currently retained items,
+    /// the oldest ones are released immediately.
+    ///
+    /// If the new resend window is larger, the [`Producer`] will reserve the additional slots
+    /// as soon as they are not occupied by unread items anymore.
+    /// The number of retained items grows as the `Consumer` advances.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the capacity,
+    /// if the ring buffer was created with [`RingBuffer::with_acks()`]
+    /// or if it uses [`OverflowPolicy::OverwriteOldest`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(4, 0);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// c.set_resend_window(2);
+    /// assert_eq!(p.slots(), 0);
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(c.pop(), Ok(20));
+    /// assert_eq!(c.retained(), 2);
+    /// assert_eq!(p.slots(), 1);
+    ///
+    /// c.set_resend_window(1);
+    /// assert_eq!(c.retained(), 1);
+    /// assert_eq!(p.slots(), 2);
+    /// ```
+    pub fn set_resend_window(&mut self, n: usize) {
+        assert!(
+            n <= self.buffer.capacity,
+            "Resend window cannot exceed capacity"
+        );
+        assert!(
+            !self.buffer.ack_required,
+            "Resend window cannot be changed if acknowledgements are required"
+        );
+        assert!(
+            self.buffer.overflow_policy != OverflowPolicy::OverwriteOldest,
+            "Resend window is not supported when overwriting the oldest items"
+        );
+        let head = self.cached_head.get();
+        if self.buffer.resend_window() == 0 {
+            // Without a resend window, `release` is not kept up to date.
+            self.buffer.release.store(head, Ordering::Relaxed);
+        } else {
+            self.release_retained(head, n);
+        }
+        // NB: The producer loads this after loading `head`.
+        //     The released slots become available together with the smaller window.
+        //     Items beyond the previous window are only retained when moving `head`,
+        //     which makes the larger window visible to the producer beforehand.
+        self.buffer.resend_window.store(n, Ordering::Release);
+        self.buffer.notify_producer();
     }
 
     /// Returns `true` if there are currently no slots available for reading.
@@ -631,7 +1640,7 @@ impl<T> Consumer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (p, c) = RingBuffer::<f32>::new(1);
+    /// let (p, c) = RingBuffer::<f32>::new(1, 0);
     ///
     /// assert!(c.is_empty());
     /// ```
@@ -641,7 +1650,7 @@ impl<T> Consumer<T> {
     ///
     /// ```
     /// # use rtrb::RingBuffer;
-    /// # let (p, c) = RingBuffer::<f32>::new(1);
+    /// # let (p, c) = RingBuffer::<f32>::new(1, 0);
     /// if c.is_empty() {
     ///     // The buffer might be empty, but it might as well not be
     ///     // if an item was just produced on another thread.
@@ -652,13 +1661,13 @@ impl<T> Consumer<T> {
     ///
     /// ```
     /// # use rtrb::RingBuffer;
-    /// # let (p, c) = RingBuffer::<f32>::new(1);
+    /// # let (p, c) = RingBuffer::<f32>::new(1, 0);
     /// if !c.is_empty() {
     ///     // At least one slot is guaranteed to be available for reading.
     /// }
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.next_head().is_none()
+        !self.has_unread()
     }
 
     /// Returns `true` if the corresponding [`Producer`] has been destroyed.
@@ -672,9 +1681,9 @@ impl<T> Consumer<T> {
     /// ```
     /// use rtrb::RingBuffer;
     ///
-    /// let (mut p, mut c) = RingBuffer::new(7);
+    /// let (mut p, mut c) = RingBuffer::new(7, 0);
     /// assert!(!c.is_abandoned());
-    /// assert_eq!(p.push(10), Ok(()));
+    /// assert_eq!(p.push(10), Ok(0));
     /// drop(p);
     /// assert!(c.is_abandoned());
     /// // The items that are left in the ring buffer can still be consumed:
@@ -686,7 +1695,7 @@ impl<T> Consumer<T> {
     ///
     /// ```
     /// # use rtrb::RingBuffer;
-    /// # let (p, c) = RingBuffer::<i32>::new(1);
+    /// # let (p, c) = RingBuffer::<i32>::new(1, 0);
     /// if !c.is_abandoned() {
     ///     // Right now, the producer might still be alive, but it might as well not be
     ///     // if another thread has just dropped it.
@@ -697,7 +1706,7 @@ impl<T> Consumer<T> {
     ///
     /// ```
     /// # use rtrb::RingBuffer;
-    /// # let (p, c) = RingBuffer::<i32>::new(1);
+    /// # let (p, c) = RingBuffer::<i32>::new(1, 0);
     /// if c.is_abandoned() {
     ///     // This is needed since Rust 1.74.0, see https://github.com/mgeier/rtrb/issues/114:
     ///     std::sync::atomic::fence(std::sync::atomic::Ordering::Acquire);
@@ -705,7 +1714,7 @@ impl<T> Consumer<T> {
     /// }
     /// ```
     pub fn is_abandoned(&self) -> bool {
-        Arc::strong_count(&self.buffer) < 2
+        self.buffer.abandoned.load(Ordering::Relaxed)
     }
 
     /// Returns a read-only reference to the ring buffer.
@@ -718,7 +1727,22 @@ impl<T> Consumer<T> {
     /// This is a strict subset of the functionality implemented in `read_chunk()`.
     /// For performance, this special case is immplemented separately.
     fn next_head(&self) -> Option<usize> {
-        let head = self.cached_head.get();
+        if !self.has_unread() {
+            return None;
+        }
+        self.pin();
+        // With a capacity of 1, the producer might have overwritten the only item
+        // (and might still be writing the new one) before the pin took effect.
+        if !self.has_unread() {
+            self.unpin();
+            return None;
+        }
+        Some(self.cached_head.get())
+    }
+
+    /// Returns `true` if an item is available for reading, without pinning it.
+    fn has_unread(&self) -> bool {
+        let head = self.unread_head();
 
         // Check if the queue is *possibly* empty.
         if head == self.cached_tail.get() {
@@ -727,175 +1751,1602 @@ impl<T> Consumer<T> {
             self.cached_tail.set(tail);
 
             // ... and check if it's *really* empty.
-            if head == tail {
-                return None;
-            }
+            return head != tail;
         }
-        Some(head)
+        true
     }
 
-    /// Get read-only access to the history window
-    pub fn history(&self) -> HistoryWindow<'_, T> {
-        // Refresh positions to ensure current state
-        let head = self.cached_head.get();
-        let tail = self.buffer.tail.load(Ordering::Acquire);
-        self.cached_tail.set(tail);
-        
-        let distance = self.buffer.distance(head, tail);
-        
-        HistoryWindow {
-            buffer: &self.buffer,
-            start: head,
-            length: distance,
-            _marker: PhantomData,
+    /// Returns the position of the oldest unread item, without pinning it.
+    ///
+    /// Unless the consumer is pinned, the producer might have moved `head`
+    /// past `cached_head` by overwriting items.
+    fn unread_head(&self) -> usize {
+        if self.buffer.overflow_policy == OverflowPolicy::OverwriteOldest && !self.pinned.get() {
+            self.buffer.head.load(Ordering::Acquire) & !PINNED
+        } else {
+            self.cached_head.get()
         }
     }
 
-    /// Get the current head position
-    pub fn head(&self) -> usize {
-        self.buffer.tail.load(Ordering::Acquire);
-        self.cached_head.get()
+    /// Stops the producer from overwriting unread items until `head` is stored again.
+    ///
+    /// If items have been overwritten before, `cached_head` is moved forward
+    /// and the lost items are recorded in `lagged`.
+    /// Nothing happens unless [`OverflowPolicy::OverwriteOldest`] is used.
+    fn pin(&self) {
+        if self.buffer.overflow_policy != OverflowPolicy::OverwriteOldest || self.pinned.get() {
+            return;
+        }
+        let head = self.buffer.head.fetch_or(PINNED, Ordering::Acquire);
+        self.pinned.set(true);
+        let period = 2 * self.buffer.capacity;
+        if period == 0 {
+            return;
+        }
+        // The producer moves `head` before counting, so the last overwritten item
+        // might be missing from the count, but no more items can be overwritten now.
+        let counted = self
+            .buffer
+            .overflowed
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.overflow_seen.get());
+        let distance = self.buffer.distance(self.cached_head.get(), head);
+        let skipped = if counted % period == distance {
+            counted
+        } else {
+            counted.wrapping_add(1)
+        };
+        if skipped != 0 {
+            if self.lagged.get() == 0 {
+                self.lagged_seq.set(self.head_seq.get());
+            }
+            self.lost.set(self.lost.get() + skipped as u64);
+            self.overflow_seen
+                .set(self.overflow_seen.get().wrapping_add(skipped));
+            self.cached_head.set(head);
+            // The cached tail might be behind the new head.
+            self.cached_tail
+                .set(self.buffer.tail.load(Ordering::Acquire));
+            self.head_seq.set(self.head_seq.get() + skipped as u64);
+            self.lagged.set(self.lagged.get() + skipped as u64);
+        }
     }
-}
-
-/// Read-only view into the ring buffer's history
-#[derive(Debug)]
-pub struct HistoryWindow<'a, T> {
-    buffer: &'a RingBuffer<T>,
-    start: usize,     // Start position
-    length: usize,    // Number of messages in history
-    _marker: PhantomData<&'a T>,
-}
 
-impl<'a, T> HistoryWindow<'a, T> {
-    /// Get message by index
-    pub fn get(&self, index: usize) -> Option<&T> {
-        if index < self.start || index >= self.start + self.length {
-            return None;
+    /// Allows the producer to overwrite unread items again, see `pin()`.
+    fn unpin(&self) {
+        if self.pinned.get() {
+            self.store_head(self.cached_head.get());
         }
-
-        // SAFETY: Index is within valid range
-        Some(unsafe { &*self.buffer.slot_ptr(index) })
     }
 
-    /// Iterate over messages in storage order
-    pub fn iter(&self) -> HistoryIter<'_, T> {
-        HistoryIter {
-            window: self,
-            current: self.start,
-            remaining: self.length,
+    /// Returns the first sequence number and the number of overwritten items
+    /// that have not been reported yet, which counts as reporting them.
+    fn take_lagged(&self) -> Option<(u64, u64)> {
+        if self.lagged.get() == 0 {
+            return None;
         }
+        Some((self.lagged_seq.get(), self.lagged.replace(0)))
     }
 
-    /// Get the starting index
-    pub fn start_index(&self) -> usize {
-        self.start
+    /// Makes the slots before `head` available to the producer, which also unpins.
+    ///
+    /// With [`OverflowPolicy::OverwriteOldest`], `pin()` must have been called before.
+    fn store_head(&self, head: usize) {
+        self.buffer.head.store(head, Ordering::Release);
+        self.buffer.notify_producer();
+        self.cached_head.set(head);
+        self.pinned.set(false);
     }
 
-    /// Get the ending index
-    pub fn end_index(&self) -> usize {
-        self.start + self.length
+    /// Returns the item at `head`, retaining it in the resend window if possible.
+    ///
+    /// Items that fall out of the resend window are dropped.
+    fn take_retaining(&self, head: usize) -> T {
+        let next = self.buffer.increment1(head);
+        if let Some(clone_fn) = self.buffer.clone_fn {
+            // SAFETY: head points to an initialized slot.
+            let value = clone_fn(unsafe { &*self.buffer.slot_ptr(head) });
+            self.release_retained(next, self.buffer.resend_window());
+            value
+        } else {
+            // The item is moved out, which leaves a gap in the retained items.
+            self.clear_retained(head, next);
+            // SAFETY: head points to an initialized slot.
+            unsafe { self.buffer.slot_ptr(head).read() }
+        }
     }
 
-    /// Get the number of messages in the history window
-    pub fn len(&self) -> usize {
-        self.length
+    /// Drops the oldest retained items until at most `keep` of them precede `head`.
+    ///
+    /// Selectively acknowledged items at the start of the retained items are dropped as well.
+    ///
+    /// This must happen before `head` is published to the producer.
+    fn release_retained(&self, head: usize, keep: usize) {
+        let start = self.buffer.release.load(Ordering::Relaxed);
+        let mut release = start;
+        let mut retained = self.buffer.distance(release, head);
+        while retained > keep || (retained > 0 && self.is_sacked(release)) {
+            self.set_sacked(release, false);
+            // NB: Items without drop glue might be read concurrently, see Producer::history().
+            if core::mem::needs_drop::<T>() {
+                // SAFETY: All slots between release and head are initialized.
+                unsafe { self.buffer.slot_ptr(release).drop_in_place() };
+            }
+            release = self.buffer.increment1(release);
+            retained -= 1;
+        }
+        if release != start {
+            // If acknowledgements are required, the producer synchronizes with this.
+            self.buffer.release.store(release, Ordering::Release);
+        }
     }
 
-    /// Check if the history window is empty
-    pub fn is_empty(&self) -> bool {
-        self.length == 0
+    /// Returns `true` if the retained item at position `pos` has been selectively acknowledged.
+    fn is_sacked(&self, pos: usize) -> bool {
+        if self.sacked.is_empty() {
+            return false;
+        }
+        let index = self.buffer.collapse_position(pos);
+        self.sacked[index / USIZE_BITS].get() & (1 << (index % USIZE_BITS)) != 0
     }
-}
-
-/// Iterator over history window
-#[derive(Debug)]
-pub struct HistoryIter<'a, T> {
-    window: &'a HistoryWindow<'a, T>,
-    current: usize,
-    remaining: usize,
-}
-
-impl<'a, T> Iterator for HistoryIter<'a, T> {
-    type Item = &'a T;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.remaining == 0 {
-            return None;
+    /// Marks the item at position `pos` as selectively acknowledged (or not).
+    fn set_sacked(&self, pos: usize, sacked: bool) {
+        if self.sacked.is_empty() {
+            return;
+        }
+        let index = self.buffer.collapse_position(pos);
+        let word = &self.sacked[index / USIZE_BITS];
+        let mask = 1 << (index % USIZE_BITS);
+        if sacked {
+            word.set(word.get() | mask);
+        } else {
+            word.set(word.get() & !mask);
         }
-
-        let result = self.window.get(self.current);
-        self.current = self.window.buffer.increment1(self.current);
-        self.remaining -= 1;
-        result
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.remaining, Some(self.remaining))
+    /// Drops all items retained before `head` and starts retaining again at `next`.
+    ///
+    /// This is used when the items between `head` and `next` are not retained
+    /// (because they are moved out or dropped).
+    /// Nothing happens if there is no resend window or if no items were consumed.
+    fn clear_retained(&self, head: usize, next: usize) {
+        if self.buffer.resend_window() == 0 || head == next {
+            return;
+        }
+        self.release_retained(head, 0);
+        // After a rewind, some of the skipped items may have been acknowledged already.
+        let mut pos = head;
+        while pos != next {
+            self.set_sacked(pos, false);
+            pos = self.buffer.increment1(pos);
+        }
+        self.buffer.release.store(next, Ordering::Release);
     }
-}
-
-/// Extension trait used to provide a [`copy_to_uninit()`](CopyToUninit::copy_to_uninit)
-/// method on built-in slices.
-///
-/// This can be used to safely copy data to the slices returned from
-/// [`WriteChunkUninit::as_mut_slices()`].
-///
-/// To use this, the trait has to be brought into scope, e.g. with:
-///
-/// ```
-/// use rtrb::CopyToUninit;
-/// ```
-pub trait CopyToUninit<T: Copy> {
-    /// Copies contents to a possibly uninitialized slice.
-    fn copy_to_uninit<'a>(&self, dst: &'a mut [MaybeUninit<T>]) -> &'a mut [T];
-}
 
-impl<T: Copy> CopyToUninit<T> for [T] {
-    /// Copies contents to a possibly uninitialized slice.
+    /// Acknowledges all items up to and including the sequence number `seq`.
     ///
-    /// # Panics
+    /// Acknowledged items are dropped and their slots can be written again by the
+    /// [`Producer`] if the ring buffer has been created with [`RingBuffer::with_acks()`].
+    /// Otherwise, this can be used to release retained items
+    /// before they fall out of the resend window.
     ///
-    /// This function will panic if the two slices have different lengths.
-    fn copy_to_uninit<'a>(&self, dst: &'a mut [MaybeUninit<T>]) -> &'a mut [T] {
-        assert_eq!(
-            self.len(),
-            dst.len(),
-            "source slice length does not match destination slice length"
-        );
-        let dst_ptr = dst.as_mut_ptr().cast();
-        // SAFETY: The lengths have been checked to be equal and
-        // the mutable reference makes sure that there is no overlap.
-        unsafe {
-            self.as_ptr().copy_to_nonoverlapping(dst_ptr, self.len());
-            core::slice::from_raw_parts_mut(dst_ptr, self.len())
+    /// Acknowledging items that have already been released has no effect.
+    ///
+    /// # Errors
+    ///
+    /// If the item with the sequence number `seq` has not been consumed yet,
+    /// [`AckError::NotConsumed`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{AckError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_acks(3);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(c.pop(), Ok(20));
+    /// assert_eq!(c.ack(2), Err(AckError::NotConsumed));
+    /// assert_eq!(c.ack(0), Ok(()));
+    /// assert_eq!(c.retained(), 1);
+    /// assert_eq!(c.ack(0), Ok(()));
+    /// assert_eq!(c.retained(), 1);
+    /// ```
+    pub fn ack(&mut self, seq: u64) -> Result<(), AckError> {
+        let head_seq = self.head_seq.get();
+        if seq >= head_seq {
+            return Err(AckError::NotConsumed);
+        }
+        let keep = head_seq - seq - 1;
+        if keep < self.retained() as u64 {
+            self.release_retained(self.cached_head.get(), keep as usize);
+            self.buffer.notify_producer();
         }
+        Ok(())
     }
-}
-
-/// Error type for [`Consumer::pop()`].
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum PopError {
-    /// The queue was empty.
-    Empty,
-}
 
-#[cfg(feature = "std")]
-impl std::error::Error for PopError {}
+    /// Acknowledges the single item with the sequence number `seq`.
+    ///
+    /// Unlike [`Consumer::ack()`], this allows acknowledging items out of order.
+    /// Acknowledged items are released as soon as all older items have been acknowledged,
+    /// until then they stay in the ring buffer and can still be re-delivered
+    /// after [`Consumer::rewind()`].
+    /// The remaining gaps can be obtained with [`Consumer::unacked_ranges()`].
+    ///
+    /// Acknowledging items that have already been released has no effect.
+    ///
+    /// # Errors
+    ///
+    /// If the item with the sequence number `seq` has not been consumed yet,
+    /// [`AckError::NotConsumed`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_acks(4);
+    /// for i in 0..4 {
+    ///     assert_eq!(p.push(i * 10), Ok(i));
+    ///     assert_eq!(c.pop(), Ok(i * 10));
+    /// }
+    /// assert_eq!(c.selective_ack(1), Ok(()));
+    /// assert_eq!(c.selective_ack(3), Ok(()));
+    /// assert_eq!(c.unacked_ranges().collect::<Vec<_>>(), [0..1, 2..3]);
+    /// assert_eq!(c.retained(), 4);
+    /// assert_eq!(c.selective_ack(0), Ok(()));
+    /// assert_eq!(c.unacked_ranges().collect::<Vec<_>>(), [2..3]);
+    /// assert_eq!(c.retained(), 2);
+    /// ```
+    pub fn selective_ack(&mut self, seq: u64) -> Result<(), AckError> {
+        let head_seq = self.head_seq.get();
+        if seq >= head_seq {
+            return Err(AckError::NotConsumed);
+        }
+        let head = self.cached_head.get();
+        let retained = self.retained();
+        let offset = head_seq - seq - 1;
+        if offset < retained as u64 {
+            let pos = self.buffer.decrement(head, offset as usize + 1);
+            self.set_sacked(pos, true);
+            self.release_retained(head, retained);
+            self.buffer.notify_producer();
+        }
+        Ok(())
+    }
 
-impl fmt::Display for PopError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            PopError::Empty => "empty ring buffer".fmt(f),
+    /// Returns an iterator over the ranges of sequence numbers
+    /// of retained items that have not been acknowledged.
+    ///
+    /// Items that have been acknowledged with [`Consumer::selective_ack()`]
+    /// but are still retained (because older items have not been acknowledged)
+    /// are skipped.
+    ///
+    /// This doesn't allocate, see the example in [`Consumer::selective_ack()`].
+    pub fn unacked_ranges(&self) -> UnackedRanges<'_, T> {
+        let retained = self.retained();
+        UnackedRanges {
+            consumer: self,
+            pos: self.buffer.decrement(self.cached_head.get(), retained),
+            seq: self.head_seq.get() - retained as u64,
+            remaining: retained,
         }
     }
-}
 
-/// Error type for [`Consumer::peek()`].
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    /// Get read-only access to the items that have been consumed but not acknowledged
+    ///
+    /// These are the retained items, see [`Consumer::ack()`] and [`Consumer::rewind()`].
+    /// Items that have been acknowledged with [`Consumer::selective_ack()`]
+    /// but are not yet released are included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_acks(3);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(c.pop(), Ok(20));
+    /// assert_eq!(c.ack(0), Ok(()));
+    ///
+    /// let unacked = c.unacked();
+    /// assert_eq!(unacked.start_index(), 1);
+    /// assert_eq!(unacked.iter().collect::<Vec<_>>(), [&20]);
+    /// ```
+    pub fn unacked(&self) -> HistoryWindow<'_, T> {
+        let retained = self.retained();
+        HistoryWindow {
+            buffer: &self.buffer,
+            start: self.buffer.decrement(self.cached_head.get(), retained),
+            start_seq: self.head_seq.get() - retained as u64,
+            length: retained,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get read-only access to the history window
+    ///
+    /// The window contains the retained items (see [`Consumer::rewind()`])
+    /// followed by all items that are available for reading.
+    ///
+    /// With [`OverflowPolicy::OverwriteOldest`], the items that are available for reading
+    /// are not part of the window, because the [`Producer`] might overwrite them
+    /// while the window exists.
+    pub fn history(&self) -> HistoryWindow<'_, T> {
+        let head = self.cached_head.get();
+        let retained = self.retained();
+        let start = self.buffer.decrement(head, retained);
+        let pending = if self.buffer.overflow_policy == OverflowPolicy::OverwriteOldest {
+            0
+        } else {
+            // Refresh positions to ensure current state
+            let tail = self.buffer.tail.load(Ordering::Acquire);
+            self.cached_tail.set(tail);
+            self.buffer.distance(head, tail)
+        };
+
+        HistoryWindow {
+            buffer: &self.buffer,
+            start,
+            start_seq: self.head_seq.get() - retained as u64,
+            length: retained + pending,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the sequence number of the next item to be read
+    pub fn head(&self) -> u64 {
+        self.head_seq.get()
+    }
+
+    /// Returns the total number of unread items that have been overwritten.
+    ///
+    /// This only counts items lost with [`OverflowPolicy::OverwriteOldest`],
+    /// including the ones that have not yet been reported by [`PopError::Lagged`].
+    /// Items that are never pushed because of [`OverflowPolicy::DropNewest`]
+    /// don't get a sequence number and are only counted in [`RingBuffer::overflowed()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{OverflowPolicy, PopError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::OverwriteOldest);
+    /// for i in 0..5 {
+    ///     assert_eq!(p.push(i), Ok(i as u64));
+    /// }
+    /// assert_eq!(c.pop(), Err(PopError::Lagged { first: 0, skipped: 3 }));
+    /// assert_eq!(c.lost_total(), 3);
+    /// assert_eq!(c.pop(), Ok(3));
+    /// assert_eq!(p.push(5), Ok(5));
+    /// assert_eq!(p.push(6), Ok(6));
+    /// assert_eq!(c.pop(), Err(PopError::Lagged { first: 4, skipped: 1 }));
+    /// assert_eq!(c.lost_total(), 4);
+    /// ```
+    pub fn lost_total(&self) -> u64 {
+        if self.buffer.overflow_policy != OverflowPolicy::OverwriteOldest {
+            return self.lost.get();
+        }
+        // This doesn't pin, which would stop the producer from overwriting.
+        // The producer moves `head` before counting, the last overwritten item might be missing.
+        // `pin()` might already have taken it into account, which makes this negative.
+        let pending = self
+            .buffer
+            .overflowed
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.overflow_seen.get()) as isize;
+        self.lost.get() + pending.max(0) as u64
+    }
+
+    /// Delivers all items starting with sequence number `seq`,
+    /// first the retained ones and then new ones as they are pushed.
+    ///
+    /// If `seq` refers to a retained item, the `Consumer` is rewound
+    /// (see [`Consumer::rewind()`]).
+    /// If it refers to an item that has not been read yet, the items before it are consumed
+    /// (and retained in the resend window, like with
+    /// [`ReadChunk::commit_retaining()`](chunks::ReadChunk::commit_retaining)).
+    /// The returned [`Subscription`] pops from the `Consumer`,
+    /// so there is neither a gap nor a duplicate when it switches from retained to new items.
+    ///
+    /// # Errors
+    ///
+    /// If the item has already been released from the ring buffer,
+    /// [`HistoryError::Expired`] is returned.
+    /// If it has not yet been written, [`HistoryError::NotYetWritten`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{HistoryError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    /// for i in 0..3 {
+    ///     assert_eq!(p.push(i * 10), Ok(i));
+    ///     assert_eq!(c.pop(), Ok(i * 10));
+    /// }
+    /// assert_eq!(c.subscribe_from(0).unwrap_err(), HistoryError::Expired);
+    ///
+    /// let mut subscription = c.subscribe_from(1).unwrap();
+    /// assert_eq!(subscription.next(), Some((1, 10)));
+    /// assert_eq!(subscription.next(), Some((2, 20)));
+    /// assert!(subscription.is_live());
+    /// assert_eq!(subscription.next(), None);
+    /// assert_eq!(p.push(30), Ok(3));
+    /// assert_eq!(subscription.next(), Some((3, 30)));
+    /// ```
+    pub fn subscribe_from(&mut self, seq: u64) -> Result<Subscription<'_, T>, HistoryError> {
+        let pending = self.slots();
+        let head_seq = self.head_seq.get();
+        let live_seq = head_seq;
+        if seq < head_seq {
+            self.rewind((head_seq - seq) as usize)
+                .map_err(|_| HistoryError::Expired)?;
+        } else if seq > head_seq {
+            let n = seq - head_seq;
+            if n > pending as u64 {
+                return Err(HistoryError::NotYetWritten);
+            }
+            if let Ok(chunk) = self.read_chunk(n as usize) {
+                chunk.commit_retaining(n as usize);
+            }
+        }
+        Ok(Subscription {
+            consumer: self,
+            live_seq,
+        })
+    }
+
+    /// Starts reading a batch of items that is only consumed if it is committed.
+    ///
+    /// Items popped with [`Transaction::pop()`] are not made available to the [`Producer`]
+    /// until [`Transaction::commit()`] is called.
+    /// If the [`Transaction`] is dropped without committing,
+    /// the items are delivered again by the next [`Consumer::pop()`] (or [`Consumer::begin()`]).
+    ///
+    /// Committed items are treated like items committed with
+    /// [`ReadChunk::commit_retaining()`](chunks::ReadChunk::commit_retaining),
+    /// i.e. they are kept in the resend window.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{PopError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::new(4, 0);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    ///
+    /// let mut tx = c.begin();
+    /// assert_eq!(tx.pop(), Ok(10));
+    /// assert_eq!(tx.pop(), Ok(20));
+    /// assert_eq!(tx.pop(), Err(PopError::Empty));
+    /// tx.rollback(); // Something went wrong, this is the same as dropping `tx`
+    ///
+    /// let mut tx = c.begin();
+    /// assert_eq!(tx.pop(), Ok(10));
+    /// assert_eq!(tx.pop(), Ok(20));
+    /// tx.commit();
+    /// assert_eq!(c.pop(), Err(PopError::Empty));
+    /// ```
+    pub fn begin(&mut self) -> Transaction<'_, T> {
+        self.pin();
+        Transaction {
+            head: self.cached_head.get(),
+            head_seq: self.head_seq.get(),
+            lagged: self.lagged.get(),
+            lagged_seq: self.lagged_seq.get(),
+            consumer: self,
+        }
+    }
+}
+
+/// Items from a given sequence number onwards, first retained ones and then live ones
+///
+/// This `struct` is created by [`Consumer::subscribe_from()`].
+#[derive(Debug)]
+pub struct Subscription<'a, T> {
+    consumer: &'a mut Consumer<T>,
+    live_seq: u64, // Sequence number of the first item that was not retained
+}
+
+impl<T> Subscription<'_, T> {
+    /// Returns `true` if all retained items have been delivered.
+    pub fn is_live(&self) -> bool {
+        self.consumer.head() >= self.live_seq
+    }
+
+    /// Get the sequence number of the next item to be delivered
+    pub fn head(&self) -> u64 {
+        self.consumer.head()
+    }
+}
+
+impl<T> Iterator for Subscription<'_, T> {
+    type Item = (u64, T);
+
+    /// Pops the next item along with its sequence number.
+    ///
+    /// `None` is returned if the queue is empty,
+    /// but more items can be returned after the [`Producer`] has pushed them.
+    /// Items lost with [`OverflowPolicy::OverwriteOldest`] are skipped,
+    /// which shows up as a gap in the sequence numbers.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.consumer.pop_with_seq() {
+                Ok(item) => return Some(item),
+                Err(PopError::Lagged { .. }) => continue,
+                Err(PopError::Empty) => return None,
+            }
+        }
+    }
+}
+
+/// A batch of items read from a [`Consumer`] that is consumed all at once
+///
+/// This `struct` is created by [`Consumer::begin()`].
+/// Dropping it without calling [`Transaction::commit()`] rolls back all pops.
+#[derive(Debug)]
+pub struct Transaction<'a, T> {
+    consumer: &'a mut Consumer<T>,
+    head: usize,     // Local read position
+    head_seq: u64,   // Sequence number at the local read position
+    lagged: u64,     // Overwritten items not yet reported by this transaction
+    lagged_seq: u64, // Sequence number of the first of them
+}
+
+impl<T> Drop for Transaction<'_, T> {
+    /// Rolls back all pops, unless the transaction has been committed.
+    fn drop(&mut self) {
+        // Items that are delivered again may be overwritten in the meantime.
+        self.consumer.unpin();
+    }
+}
+
+impl<T> Transaction<'_, T> {
+    /// Attempts to pop a clone of the next item.
+    ///
+    /// The original item stays in the ring buffer until the transaction is committed.
+    ///
+    /// # Errors
+    ///
+    /// If no more items are available, [`PopError::Empty`] is returned.
+    /// Items lost before the transaction started are reported with [`PopError::Lagged`],
+    /// like in [`Consumer::pop()`].
+    pub fn pop(&mut self) -> Result<T, PopError>
+    where
+        T: Clone,
+    {
+        if let Some(head) = self.next_head() {
+            if self.lagged != 0 {
+                let skipped = self.lagged;
+                self.lagged = 0;
+                return Err(PopError::Lagged {
+                    first: self.lagged_seq,
+                    skipped,
+                });
+            }
+            // SAFETY: head points to an initialized slot.
+            let value = unsafe { &*self.consumer.buffer.slot_ptr(head) }.clone();
+            self.head = self.consumer.buffer.increment1(head);
+            self.head_seq += 1;
+            Ok(value)
+        } else {
+            Err(PopError::Empty)
+        }
+    }
+
+    /// Attempts to pop a clone of the next item, along with its sequence number.
+    ///
+    /// This behaves like [`Transaction::pop()`].
+    ///
+    /// # Errors
+    ///
+    /// If no more items are available, an error is returned.
+    pub fn pop_with_seq(&mut self) -> Result<(u64, T), PopError>
+    where
+        T: Clone,
+    {
+        let seq = self.head_seq;
+        self.pop().map(|value| (seq, value))
+    }
+
+    /// Attempts to read the next item without popping it.
+    ///
+    /// # Errors
+    ///
+    /// If no more items are available, an error is returned.
+    pub fn peek(&self) -> Result<&T, PeekError> {
+        if let Some(head) = self.next_head() {
+            // SAFETY: head points to an initialized slot.
+            Ok(unsafe { &*self.consumer.buffer.slot_ptr(head) })
+        } else {
+            Err(PeekError::Empty)
+        }
+    }
+
+    /// Returns the number of items popped so far.
+    pub fn popped(&self) -> usize {
+        let c = &self.consumer;
+        c.buffer.distance(c.cached_head.get(), self.head)
+    }
+
+    /// Get the sequence number of the next item to be read
+    pub fn head(&self) -> u64 {
+        self.head_seq
+    }
+
+    /// Makes the slots of all popped items available to the [`Producer`].
+    ///
+    /// If there is a resend window, the original items are retained,
+    /// otherwise they are dropped.
+    pub fn commit(self) {
+        let c = &*self.consumer;
+        let mut pos = c.cached_head.get();
+        if c.buffer.resend_window() == 0 {
+            while pos != self.head {
+                // SAFETY: All slots up to the local head are initialized.
+                unsafe { c.buffer.slot_ptr(pos).drop_in_place() };
+                pos = c.buffer.increment1(pos);
+            }
+        } else {
+            c.release_retained(self.head, c.buffer.resend_window());
+        }
+        c.store_head(self.head);
+        c.head_seq.set(self.head_seq);
+        c.lagged.set(self.lagged);
+    }
+
+    /// Discards the transaction, all popped items are delivered again.
+    ///
+    /// This is the same as dropping the transaction.
+    pub fn rollback(self) {}
+
+    /// Get the local read position, if an item is available.
+    fn next_head(&self) -> Option<usize> {
+        let c = &self.consumer;
+        if self.head == c.cached_tail.get() {
+            let tail = c.buffer.tail.load(Ordering::Acquire);
+            c.cached_tail.set(tail);
+            if self.head == tail {
+                return None;
+            }
+        }
+        Some(self.head)
+    }
+}
+
+/// Iterator over the gaps in the acknowledged items
+///
+/// This `struct` is created by [`Consumer::unacked_ranges()`].
+#[derive(Debug)]
+pub struct UnackedRanges<'a, T> {
+    consumer: &'a Consumer<T>,
+    pos: usize,
+    seq: u64,
+    remaining: usize,
+}
+
+impl<T> Iterator for UnackedRanges<'_, T> {
+    type Item = Range<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let c = self.consumer;
+        while self.remaining > 0 && c.is_sacked(self.pos) {
+            self.pos = c.buffer.increment1(self.pos);
+            self.seq += 1;
+            self.remaining -= 1;
+        }
+        if self.remaining == 0 {
+            return None;
+        }
+        let start = self.seq;
+        while self.remaining > 0 && !c.is_sacked(self.pos) {
+            self.pos = c.buffer.increment1(self.pos);
+            self.seq += 1;
+            self.remaining -= 1;
+        }
+        Some(start..self.seq)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.remaining + 1) / 2))
+    }
+}
+
+/// Read-only view into the ring buffer's history
+#[derive(Debug)]
+pub struct HistoryWindow<'a, T> {
+    buffer: &'a RingBuffer<T>,
+    start: usize,   // Start position
+    start_seq: u64, // Sequence number at start position
+    length: usize,  // Number of messages in history
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> HistoryWindow<'a, T> {
+    /// Get message by sequence number
+    ///
+    /// # Errors
+    ///
+    /// If the message has already been released from the ring buffer,
+    /// [`HistoryError::Expired`] is returned.
+    /// If it has not yet been written when the window was obtained,
+    /// [`HistoryError::NotYetWritten`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{HistoryError, RingBuffer};
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(4, 1);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(c.pop(), Ok(20));
+    ///
+    /// let history = c.history();
+    /// assert_eq!(history.get(0), Err(HistoryError::Expired));
+    /// assert_eq!(history.get(1), Ok(&20));
+    /// assert_eq!(history.get(2), Ok(&30));
+    /// assert_eq!(history.get(3), Err(HistoryError::NotYetWritten));
+    /// ```
+    pub fn get(&self, seq: u64) -> Result<&T, HistoryError> {
+        if seq < self.start_seq {
+            return Err(HistoryError::Expired);
+        }
+        let offset = seq - self.start_seq;
+        if offset >= self.length as u64 {
+            return Err(HistoryError::NotYetWritten);
+        }
+        let pos = self.buffer.increment(self.start, offset as usize);
+
+        // SAFETY: All slots in the window are initialized.
+        Ok(unsafe { &*self.buffer.slot_ptr(pos) })
+    }
+
+    /// Iterate over messages in storage order
+    pub fn iter(&self) -> HistoryIter<'_, T> {
+        HistoryIter {
+            buffer: self.buffer,
+            current: self.start,
+            remaining: self.length,
+        }
+    }
+
+    /// Get all messages as two slices in sequence order
+    ///
+    /// If the first slice contains all messages, the second one is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(3, 1);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(c.pop(), Ok(20));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// assert_eq!(p.push(40), Ok(3));
+    ///
+    /// assert_eq!(c.history().as_slices(), (&[20, 30][..], &[40][..]));
+    /// ```
+    pub fn as_slices(&self) -> (&'a [T], &'a [T]) {
+        let start = self.buffer.collapse_position(self.start);
+        let first_len = self.length.min(self.buffer.capacity - start);
+        // SAFETY: All slots in the window are initialized.
+        unsafe {
+            (
+                core::slice::from_raw_parts(self.buffer.data_ptr.add(start), first_len),
+                core::slice::from_raw_parts(self.buffer.data_ptr, self.length - first_len),
+            )
+        }
+    }
+
+    /// Get a narrower window containing the messages with sequence numbers in `range`
+    ///
+    /// # Errors
+    ///
+    /// If the range starts before the oldest message, [`HistoryError::Expired`] is returned.
+    /// If it ends after the newest message, [`HistoryError::NotYetWritten`] is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range starts after it ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{HistoryError, RingBuffer};
+    ///
+    /// let (mut p, c) = RingBuffer::new(4, 0);
+    /// for i in 0..4 {
+    ///     assert_eq!(p.push(i * 10), Ok(i));
+    /// }
+    /// let history = c.history();
+    /// let range = history.range(1..3).unwrap();
+    /// assert_eq!(range.start_index(), 1);
+    /// assert_eq!(range.iter().copied().collect::<Vec<_>>(), [10, 20]);
+    /// assert_eq!(history.range(2..5).unwrap_err(), HistoryError::NotYetWritten);
+    /// ```
+    pub fn range(&self, range: Range<u64>) -> Result<HistoryWindow<'a, T>, HistoryError> {
+        assert!(range.start <= range.end, "Range start must not exceed end");
+        if range.start < self.start_seq {
+            return Err(HistoryError::Expired);
+        }
+        if range.end > self.end_index() {
+            return Err(HistoryError::NotYetWritten);
+        }
+        let offset = (range.start - self.start_seq) as usize;
+        Ok(HistoryWindow {
+            buffer: self.buffer,
+            start: self.buffer.increment(self.start, offset),
+            start_seq: range.start,
+            length: (range.end - range.start) as usize,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Get a narrower window containing (at most) the `n` most recent messages
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, c) = RingBuffer::new(4, 0);
+    /// for i in 0..3 {
+    ///     assert_eq!(p.push(i * 10), Ok(i));
+    /// }
+    /// let history = c.history();
+    /// assert_eq!(history.last(2).start_index(), 1);
+    /// assert_eq!(history.last(2).as_slices(), (&[10, 20][..], &[][..]));
+    /// assert_eq!(history.last(5).len(), 3);
+    /// ```
+    pub fn last(&self, n: usize) -> HistoryWindow<'a, T> {
+        let skipped = self.length.saturating_sub(n);
+        HistoryWindow {
+            buffer: self.buffer,
+            start: self.buffer.increment(self.start, skipped),
+            start_seq: self.start_seq + skipped as u64,
+            length: self.length - skipped,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Binary search for a message with a comparator function
+    ///
+    /// This works like [`slice::binary_search_by()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by),
+    /// the messages have to be sorted in sequence order.
+    ///
+    /// # Errors
+    ///
+    /// If no matching message is found, the sequence number where a matching message
+    /// could be inserted (while maintaining the sorted order) is returned as error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    /// for i in 0..4 {
+    ///     assert_eq!(p.push(i * 10), Ok(i));
+    ///     assert_eq!(c.pop(), Ok(i * 10));
+    /// }
+    /// let history = c.history();
+    /// assert_eq!(history.binary_search_by(|x| x.cmp(&30)), Ok(3));
+    /// assert_eq!(history.binary_search_by(|x| x.cmp(&25)), Err(3));
+    /// ```
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<u64, u64>
+    where
+        F: FnMut(&'a T) -> core::cmp::Ordering,
+    {
+        let mut low = 0;
+        let mut high = self.length;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match f(self.item(mid)) {
+                core::cmp::Ordering::Less => low = mid + 1,
+                core::cmp::Ordering::Greater => high = mid,
+                core::cmp::Ordering::Equal => return Ok(self.start_seq + mid as u64),
+            }
+        }
+        Err(self.start_seq + low as u64)
+    }
+
+    /// Get the sequence number of the first message for which `pred` returns `false`
+    ///
+    /// This works like [`slice::partition_point()`](https://doc.rust-lang.org/std/primitive.slice.html#method.partition_point),
+    /// the messages for which `pred` returns `true` have to come first.
+    /// If `pred` returns `true` for all messages, [`HistoryWindow::end_index()`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, c) = RingBuffer::new(4, 0);
+    /// for i in 0..4 {
+    ///     assert_eq!(p.push(i * 10), Ok(i));
+    /// }
+    /// let history = c.history();
+    /// assert_eq!(history.partition_point(|x| *x < 15), 2);
+    /// assert_eq!(history.partition_point(|_| true), 4);
+    /// ```
+    pub fn partition_point<P>(&self, mut pred: P) -> u64
+    where
+        P: FnMut(&'a T) -> bool,
+    {
+        self.binary_search_by(|x| {
+            if pred(x) {
+                core::cmp::Ordering::Less
+            } else {
+                core::cmp::Ordering::Greater
+            }
+        })
+        .unwrap_or_else(|seq| seq)
+    }
+
+    /// Get a narrower window containing the messages whose keys are in `range`
+    ///
+    /// The messages have to be sorted by the key returned from `f`.
+    /// If no key is in `range`, an empty window is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// #[derive(Clone, Copy)]
+    /// struct Packet {
+    ///     time: u32,
+    /// }
+    ///
+    /// let (mut p, c) = RingBuffer::new(4, 0);
+    /// for &time in &[100, 200, 200, 300] {
+    ///     p.push(Packet { time }).unwrap();
+    /// }
+    /// let history = c.history();
+    /// let window = history.range_by_key(150.., |packet| packet.time);
+    /// assert_eq!(window.start_index(), 1);
+    /// assert_eq!(window.len(), 3);
+    /// let window = history.range_by_key(200..300, |packet| packet.time);
+    /// assert_eq!(window.start_index(), 1);
+    /// assert_eq!(window.len(), 2);
+    /// assert!(history.range_by_key(400.., |packet| packet.time).is_empty());
+    /// ```
+    pub fn range_by_key<K, R, F>(&self, range: R, mut f: F) -> HistoryWindow<'a, T>
+    where
+        K: Ord,
+        R: RangeBounds<K>,
+        F: FnMut(&'a T) -> K,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(key) => self.partition_point(|x| f(x) < *key),
+            Bound::Excluded(key) => self.partition_point(|x| f(x) <= *key),
+            Bound::Unbounded => self.start_seq,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => self.partition_point(|x| f(x) <= *key),
+            Bound::Excluded(key) => self.partition_point(|x| f(x) < *key),
+            Bound::Unbounded => self.end_index(),
+        };
+        let offset = (start - self.start_seq) as usize;
+        HistoryWindow {
+            buffer: self.buffer,
+            start: self.buffer.increment(self.start, offset),
+            start_seq: start,
+            length: end.saturating_sub(start) as usize,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the sequence number of the oldest message
+    pub fn start_index(&self) -> u64 {
+        self.start_seq
+    }
+
+    /// Get the sequence number following the newest message
+    pub fn end_index(&self) -> u64 {
+        self.start_seq + self.length as u64
+    }
+
+    /// Get the message at `offset` from the start of the window.
+    fn item(&self, offset: usize) -> &'a T {
+        debug_assert!(offset < self.length);
+        let pos = self.buffer.increment(self.start, offset);
+        // SAFETY: All slots in the window are initialized.
+        unsafe { &*self.buffer.slot_ptr(pos) }
+    }
+
+    /// Get the number of messages in the history window
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Check if the history window is empty
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Clone all messages into an owned snapshot
+    ///
+    /// Unlike the history window, the snapshot doesn't borrow the ring buffer,
+    /// it can be kept across [`Consumer::pop()`] and sent to another thread.
+    ///
+    /// This allocates, see [`HistoryWindow::snapshot_into()`] for a realtime-safe alternative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::RingBuffer;
+    ///
+    /// let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(c.pop(), Ok(10));
+    ///
+    /// let snapshot = c.history().snapshot();
+    /// assert_eq!(c.pop(), Ok(20));
+    /// std::thread::spawn(move || {
+    ///     assert_eq!(snapshot.start_index(), 0);
+    ///     assert_eq!(snapshot.as_slice(), [10, 20]);
+    /// }).join().unwrap();
+    /// ```
+    pub fn snapshot(&self) -> HistorySnapshot<T>
+    where
+        T: Clone,
+    {
+        let mut snapshot = HistorySnapshot::with_capacity(self.length);
+        self.snapshot_into(&mut snapshot);
+        snapshot
+    }
+
+    /// Clone the most recent messages into an existing snapshot
+    ///
+    /// The previous contents of `snapshot` are dropped.
+    /// At most [`HistorySnapshot::capacity()`] messages are cloned,
+    /// older ones are left out.
+    /// No memory is allocated (unless `T::clone()` does that internally).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{HistorySnapshot, RingBuffer};
+    ///
+    /// let mut snapshot = HistorySnapshot::with_capacity(2);
+    ///
+    /// let (mut p, c) = RingBuffer::new(4, 0);
+    /// for i in 0..3 {
+    ///     assert_eq!(p.push(i * 10), Ok(i));
+    /// }
+    /// c.history().snapshot_into(&mut snapshot);
+    /// assert_eq!(snapshot.start_index(), 1);
+    /// assert_eq!(snapshot.as_slice(), [10, 20]);
+    /// ```
+    pub fn snapshot_into(&self, snapshot: &mut HistorySnapshot<T>)
+    where
+        T: Clone,
+    {
+        let window = self.last(snapshot.capacity());
+        snapshot.items.clear();
+        snapshot.items.extend(window.iter().cloned());
+        snapshot.start_seq = window.start_seq;
+    }
+}
+
+/// Owned copy of messages from a [`HistoryWindow`]
+///
+/// This is created by [`HistoryWindow::snapshot()`]
+/// and can be re-filled with [`HistoryWindow::snapshot_into()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistorySnapshot<T> {
+    items: Vec<T>,
+    start_seq: u64, // Sequence number of the first item
+}
+
+impl<T> HistorySnapshot<T> {
+    /// Create an empty snapshot that can hold up to `capacity` messages
+    ///
+    /// This is the only time memory is allocated for the snapshot.
+    pub fn with_capacity(capacity: usize) -> Self {
+        HistorySnapshot {
+            items: Vec::with_capacity(capacity),
+            start_seq: 0,
+        }
+    }
+
+    /// Get message by sequence number
+    ///
+    /// # Errors
+    ///
+    /// If the message is older than the snapshot, [`HistoryError::Expired`] is returned.
+    /// If it is newer, [`HistoryError::NotYetWritten`] is returned.
+    pub fn get(&self, seq: u64) -> Result<&T, HistoryError> {
+        if seq < self.start_seq {
+            return Err(HistoryError::Expired);
+        }
+        let offset = seq - self.start_seq;
+        if offset >= self.items.len() as u64 {
+            return Err(HistoryError::NotYetWritten);
+        }
+        Ok(&self.items[offset as usize])
+    }
+
+    /// Iterate over messages in sequence order
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// Get all messages as a slice
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Get the sequence number of the oldest message
+    pub fn start_index(&self) -> u64 {
+        self.start_seq
+    }
+
+    /// Get the sequence number following the newest message
+    pub fn end_index(&self) -> u64 {
+        self.start_seq + self.items.len() as u64
+    }
+
+    /// Get the number of messages in the snapshot
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if the snapshot is empty
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Get the maximum number of messages the snapshot can hold without allocating
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Turn the snapshot into a vector of messages
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+}
+
+/// Iterator over history window
+#[derive(Debug)]
+pub struct HistoryIter<'a, T> {
+    buffer: &'a RingBuffer<T>,
+    current: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for HistoryIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        // SAFETY: All slots in the window are initialized.
+        let result = unsafe { &*self.buffer.slot_ptr(self.current) };
+        self.current = self.buffer.increment1(self.current);
+        self.remaining -= 1;
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for HistoryIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+        let pos = self.buffer.increment(self.current, self.remaining);
+        // SAFETY: All slots in the window are initialized.
+        Some(unsafe { &*self.buffer.slot_ptr(pos) })
+    }
+}
+
+impl<T> ExactSizeIterator for HistoryIter<'_, T> {}
+
+impl<T> core::iter::FusedIterator for HistoryIter<'_, T> {}
+
+/// Read-only access to the history from a third thread
+///
+/// This can only be created with [`RingBuffer::with_history_reader()`]
+/// (together with [`Producer`] and [`Consumer`]).
+///
+/// While it exists, the [`Consumer`] cannot modify unread items
+/// (see [`ReadChunk::as_mut_slices()`](chunks::ReadChunk::as_mut_slices)).
+///
+/// Reading never blocks the [`Producer`] or the [`Consumer`].
+/// Each slot has a stamp that is updated before and after it is written,
+/// items that are overwritten while being read are detected and left out.
+///
+/// Can be moved between threads,
+/// but references from different threads are not allowed
+/// (i.e. it is [`Send`] but not [`Sync`]).
+#[derive(Debug)]
+pub struct HistoryReader<T> {
+    /// A reference to the ring buffer.
+    buffer: Arc<RingBuffer<T>>,
+
+    /// The sequence number at the start of the most recent snapshot.
+    ///
+    /// The stamps only contain the lower bits of the sequence numbers,
+    /// the upper bits are restored relative to this.
+    last_seq: Cell<u64>,
+}
+
+// SAFETY: After moving a HistoryReader to another thread, there is still only a single thread
+// that can access it. Items of type T are only copied, never moved out or dropped.
+unsafe impl<T: Send> Send for HistoryReader<T> {}
+
+impl<T: Copy> HistoryReader<T> {
+    /// Get a copy of the message with the given sequence number
+    ///
+    /// Only retained and pending messages are available.
+    ///
+    /// # Errors
+    ///
+    /// If the message has already been released from the ring buffer,
+    /// [`HistoryError::Expired`] is returned.
+    /// If it has not yet been written, [`HistoryError::NotYetWritten`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{HistoryError, RingBuffer};
+    ///
+    /// let (mut p, mut c, reader) = RingBuffer::with_history_reader(4, 1);
+    /// assert_eq!(p.push(10), Ok(0));
+    /// assert_eq!(p.push(20), Ok(1));
+    /// assert_eq!(p.push(30), Ok(2));
+    /// assert_eq!(c.pop(), Ok(10));
+    /// assert_eq!(c.pop(), Ok(20));
+    ///
+    /// assert_eq!(reader.get(0), Err(HistoryError::Expired));
+    /// assert_eq!(reader.get(1), Ok(20));
+    /// assert_eq!(reader.get(2), Ok(30));
+    /// assert_eq!(reader.get(3), Err(HistoryError::NotYetWritten));
+    /// ```
+    pub fn get(&self, seq: u64) -> Result<T, HistoryError> {
+        let capacity = self.buffer.capacity;
+        if capacity == 0 {
+            return Err(HistoryError::NotYetWritten);
+        }
+        let pos = (seq % (2 * capacity as u64)) as usize;
+        let expected = stamp(seq);
+        loop {
+            let (start, tail) = self.window();
+            let offset = self.buffer.distance(start, pos);
+            let current =
+                self.buffer.stamps[self.buffer.collapse_position(pos)].load(Ordering::Relaxed);
+            if current == expected {
+                if offset < self.buffer.distance(start, tail) {
+                    match self.read(pos, seq) {
+                        Some(value) => return Ok(value),
+                        // The slot has just been overwritten, try again.
+                        None => continue,
+                    }
+                }
+                // The retained and pending items never span more than the capacity,
+                // the slot is either before `start` or not yet published at `tail`.
+                return Err(if offset >= capacity {
+                    HistoryError::Expired
+                } else {
+                    HistoryError::NotYetWritten
+                });
+            }
+            // Compare the (wrapping) sequence numbers in the stamps.
+            let diff = current.wrapping_sub(expected) & STAMP_MASK;
+            return Err(if current != 0 && diff != 0 && diff < STAMP_MASK >> 1 {
+                HistoryError::Expired
+            } else {
+                HistoryError::NotYetWritten
+            });
+        }
+    }
+
+    /// Copy all retained and pending messages into a snapshot
+    ///
+    /// This allocates memory for [`RingBuffer::capacity()`] messages,
+    /// see [`HistoryReader::snapshot_into()`] for a realtime-safe alternative.
+    pub fn snapshot(&self) -> HistorySnapshot<T> {
+        let mut snapshot = HistorySnapshot::with_capacity(self.buffer.capacity);
+        self.snapshot_into(&mut snapshot);
+        snapshot
+    }
+
+    /// Copy the most recent retained and pending messages into an existing snapshot
+    ///
+    /// The previous contents of `snapshot` are discarded.
+    /// At most [`HistorySnapshot::capacity()`] messages are copied, older ones are left out.
+    /// Messages that are overwritten while copying are left out as well,
+    /// the snapshot always contains consecutive sequence numbers.
+    /// No memory is allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rtrb::{HistorySnapshot, RingBuffer};
+    ///
+    /// let mut snapshot = HistorySnapshot::with_capacity(4);
+    ///
+    /// let (mut p, mut c, reader) = RingBuffer::with_history_reader(4, 2);
+    /// for i in 0..6 {
+    ///     assert_eq!(p.push(i * 10), Ok(i));
+    ///     assert_eq!(c.pop(), Ok(i * 10));
+    /// }
+    /// reader.snapshot_into(&mut snapshot);
+    /// assert_eq!(snapshot.start_index(), 4);
+    /// assert_eq!(snapshot.as_slice(), [40, 50]);
+    /// ```
+    pub fn snapshot_into(&self, snapshot: &mut HistorySnapshot<T>) {
+        let (start, tail) = self.window();
+        let length = self.buffer.distance(start, tail);
+        let skipped = length.saturating_sub(snapshot.capacity());
+        let mut pos = self.buffer.increment(start, skipped);
+        let mut next_seq = None;
+        snapshot.items.clear();
+        for _ in skipped..length {
+            let seq = next_seq.or_else(|| self.seq_at(pos));
+            match seq.and_then(|seq| self.read(pos, seq).map(|value| (seq, value))) {
+                Some((seq, value)) => {
+                    if snapshot.items.is_empty() {
+                        snapshot.start_seq = seq;
+                    }
+                    snapshot.items.push(value);
+                    next_seq = Some(seq + 1);
+                }
+                None => {
+                    // The slot has been overwritten, older messages are not consecutive.
+                    snapshot.items.clear();
+                    next_seq = None;
+                }
+            }
+            pos = self.buffer.increment1(pos);
+        }
+        if snapshot.items.is_empty() {
+            snapshot.start_seq = self.last_seq.get();
+        } else {
+            self.last_seq.set(snapshot.start_seq);
+        }
+    }
+
+    /// Returns a read-only reference to the ring buffer.
+    pub fn buffer(&self) -> &RingBuffer<T> {
+        &self.buffer
+    }
+
+    /// Loads the positions of the oldest retained item and the tail.
+    fn window(&self) -> (usize, usize) {
+        loop {
+            // NB: If the resend window was zero before, `release` is updated before this is stored.
+            let start = if self.buffer.resend_window.load(Ordering::Acquire) == 0 {
+                self.buffer.head.load(Ordering::Acquire) & !PINNED
+            } else {
+                self.buffer.release.load(Ordering::Acquire)
+            };
+            let tail = self.buffer.tail.load(Ordering::Acquire);
+            // If the producer has lapped `start` in the meantime, the distance is meaningless.
+            if self.buffer.distance(start, tail) <= self.buffer.capacity {
+                return (start, tail);
+            }
+        }
+    }
+
+    /// Returns the sequence number of the completely written item at position `pos`.
+    fn seq_at(&self, pos: usize) -> Option<u64> {
+        let current =
+            self.buffer.stamps[self.buffer.collapse_position(pos)].load(Ordering::Relaxed);
+        if current == 0 || current % 2 != 0 {
+            return None;
+        }
+        // The stamp only contains the lower bits, the sequence number is assumed
+        // to be close to the one from the previous snapshot.
+        let last = self.last_seq.get();
+        let diff = ((current - 2) / 2).wrapping_sub(last as usize) & STAMP_MASK;
+        let seq = if diff > STAMP_MASK >> 1 {
+            last.wrapping_sub((STAMP_MASK - diff + 1) as u64)
+        } else {
+            last.wrapping_add(diff as u64)
+        };
+        // The slot might be shared with a different position in the meantime.
+        if seq % (2 * self.buffer.capacity as u64) != pos as u64 {
+            return None;
+        }
+        Some(seq)
+    }
+
+    /// Copies the item at position `pos` if it is the completely written item `seq`.
+    fn read(&self, pos: usize, seq: u64) -> Option<T> {
+        let stamp_ref = &self.buffer.stamps[self.buffer.collapse_position(pos)];
+        let expected = stamp(seq);
+        if stamp_ref.load(Ordering::Acquire) != expected {
+            return None;
+        }
+        // SAFETY: The slot is valid and the consumer neither modifies items while `self` exists
+        // (see ReadChunk::as_mut_slices()) nor drops them (T is Copy).
+        // The producer might concurrently overwrite the slot after the item has been released.
+        // This is detected below and the (possibly torn) value is discarded.
+        let value =
+            unsafe { core::ptr::read_volatile(self.buffer.slot_ptr(pos) as *const MaybeUninit<T>) };
+        fence(Ordering::Acquire);
+        if stamp_ref.load(Ordering::Relaxed) != expected {
+            return None;
+        }
+        // SAFETY: The item has been completely written and was not modified while copying.
+        Some(unsafe { value.assume_init() })
+    }
+}
+
+impl<T> Drop for HistoryReader<T> {
+    /// Allows the [`Consumer`] to modify unread items again.
+    fn drop(&mut self) {
+        self.buffer.history_reader.store(false, Ordering::Release);
+    }
+}
+
+/// Extension trait used to provide a [`copy_to_uninit()`](CopyToUninit::copy_to_uninit)
+/// method on built-in slices.
+///
+/// This can be used to safely copy data to the slices returned from
+/// [`WriteChunkUninit::as_mut_slices()`].
+///
+/// To use this, the trait has to be brought into scope, e.g. with:
+///
+/// ```
+/// use rtrb::CopyToUninit;
+/// ```
+pub trait CopyToUninit<T: Copy> {
+    /// Copies contents to a possibly uninitialized slice.
+    fn copy_to_uninit<'a>(&self, dst: &'a mut [MaybeUninit<T>]) -> &'a mut [T];
+}
+
+impl<T: Copy> CopyToUninit<T> for [T] {
+    /// Copies contents to a possibly uninitialized slice.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the two slices have different lengths.
+    fn copy_to_uninit<'a>(&self, dst: &'a mut [MaybeUninit<T>]) -> &'a mut [T] {
+        assert_eq!(
+            self.len(),
+            dst.len(),
+            "source slice length does not match destination slice length"
+        );
+        let dst_ptr = dst.as_mut_ptr().cast();
+        // SAFETY: The lengths have been checked to be equal and
+        // the mutable reference makes sure that there is no overlap.
+        unsafe {
+            self.as_ptr().copy_to_nonoverlapping(dst_ptr, self.len());
+            core::slice::from_raw_parts_mut(dst_ptr, self.len())
+        }
+    }
+}
+
+/// Error type for [`Consumer::pop()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PopError {
+    /// The queue was empty.
+    Empty,
+    /// Unread items have been overwritten, see [`OverflowPolicy::OverwriteOldest`].
+    ///
+    /// The sequence numbers `first..first + skipped` are missing.
+    /// The next call to [`Consumer::pop()`] returns the oldest remaining item.
+    Lagged {
+        /// The sequence number of the first overwritten item.
+        first: u64,
+        /// The number of overwritten items.
+        skipped: u64,
+    },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PopError {}
+
+impl fmt::Display for PopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PopError::Empty => "empty ring buffer".fmt(f),
+            PopError::Lagged { first, skipped } => alloc::format!(
+                "{} items overwritten in ring buffer (sequence numbers {}..{})",
+                skipped,
+                first,
+                first + skipped
+            )
+            .fmt(f),
+        }
+    }
+}
+
+/// Error type for [`Consumer::rewind()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RewindError {
+    /// Fewer than the requested number of items were retained.
+    ///
+    /// Contains the number of retained items.
+    TooFewItems(usize),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RewindError {}
+
+impl fmt::Display for RewindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RewindError::TooFewItems(n) => {
+                alloc::format!("only {} items retained in ring buffer", n).fmt(f)
+            }
+        }
+    }
+}
+
+/// Error type for [`Consumer::ack()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AckError {
+    /// The item has not been consumed yet.
+    NotConsumed,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AckError {}
+
+impl fmt::Display for AckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AckError::NotConsumed => "item not yet consumed from ring buffer".fmt(f),
+        }
+    }
+}
+
+/// Error type for [`HistoryWindow::get()`] and [`Consumer::subscribe_from()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HistoryError {
+    /// The item has already been released from the ring buffer.
+    Expired,
+    /// The item has not been written yet.
+    NotYetWritten,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HistoryError {}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::Expired => "item expired from ring buffer".fmt(f),
+            HistoryError::NotYetWritten => "item not yet written to ring buffer".fmt(f),
+        }
+    }
+}
+
+/// Error type for [`Consumer::peek()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PeekError {
     /// The queue was empty.
     Empty,
+    /// The oldest item could be overwritten while it is borrowed,
+    /// see [`OverflowPolicy::OverwriteOldest`].
+    Overwritable,
 }
 
 #[cfg(feature = "std")]
@@ -905,6 +3356,7 @@ impl fmt::Display for PeekError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PeekError::Empty => "empty ring buffer".fmt(f),
+            PeekError::Overwritable => "oldest item in ring buffer may be overwritten".fmt(f),
         }
     }
 }
@@ -914,6 +3366,16 @@ impl fmt::Display for PeekError {
 pub enum PushError<T> {
     /// The queue was full.
     Full(T),
+    /// The queue had an empty slot, but it was protected by the resend window.
+    ///
+    /// If the ring buffer was created with [`RingBuffer::with_acks()`],
+    /// this means that consumed items are waiting to be acknowledged.
+    /// Otherwise, this means that the [`Consumer`] keeps up, but the protected history is in the way.
+    WindowFull(T),
+    /// The queue was full and the element has been dropped.
+    ///
+    /// This is only returned with [`OverflowPolicy::DropNewest`].
+    Dropped,
 }
 
 #[cfg(feature = "std")]
@@ -923,6 +3385,8 @@ impl<T> fmt::Debug for PushError<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PushError::Full(_) => f.pad("Full(_)"),
+            PushError::WindowFull(_) => f.pad("WindowFull(_)"),
+            PushError::Dropped => f.pad("Dropped"),
         }
     }
 }
@@ -931,6 +3395,8 @@ impl<T> fmt::Display for PushError<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PushError::Full(_) => "full ring buffer".fmt(f),
+            PushError::WindowFull(_) => "full resend window in ring buffer".fmt(f),
+            PushError::Dropped => "full ring buffer, item dropped".fmt(f),
         }
     }
 }
@@ -0,0 +1,183 @@
+#![cfg(feature = "std")]
+
+use rtrb::blocking::{
+    enable_parking, BusySpin, ExponentialBackoff, Park, WaitStep, WaitStrategy, Yield,
+};
+use rtrb::{chunks::ChunkError, PopError, PushError, RingBuffer};
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+const LONG: Duration = Duration::from_secs(60);
+
+#[test]
+fn timeouts() {
+    let (mut p, mut c) = RingBuffer::new(1, 0);
+    enable_parking(&mut p, &mut c);
+    let start = Instant::now();
+    assert_eq!(
+        c.pop_blocking(Duration::from_millis(20)),
+        Err(PopError::Empty)
+    );
+    assert_eq!(p.push_blocking(10, Duration::from_millis(20)), Ok(0));
+    assert_eq!(
+        p.push_blocking(20, Duration::from_millis(20)),
+        Err(PushError::Full(20))
+    );
+    assert_eq!(
+        c.read_chunk_blocking(2, Duration::from_millis(20))
+            .unwrap_err(),
+        ChunkError::TooFewSlots(1)
+    );
+    assert!(start.elapsed() >= Duration::from_millis(40));
+}
+
+#[test]
+fn wake_on_threshold() {
+    let (mut p, mut c) = RingBuffer::new(8, 0);
+    enable_parking(&mut p, &mut c);
+    let t = thread::spawn(move || {
+        for i in 0..5 {
+            thread::sleep(Duration::from_millis(5));
+            assert_eq!(p.push(i), Ok(i));
+        }
+        p
+    });
+    let chunk = c.read_chunk_blocking(5, LONG).unwrap();
+    assert_eq!(chunk.len(), 5);
+    chunk.commit_all();
+    let _p = t.join().unwrap();
+}
+
+#[test]
+fn wake_on_ack() {
+    let (mut p, mut c) = RingBuffer::with_acks(2);
+    enable_parking(&mut p, &mut c);
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(p.push(20), Ok(1));
+    let t = thread::spawn(move || {
+        assert_eq!(c.pop(), Ok(10));
+        assert_eq!(c.pop(), Ok(20));
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(c.ack(0), Ok(()));
+        c
+    });
+    assert_eq!(p.push_blocking(30, LONG), Ok(2));
+    let _c = t.join().unwrap();
+}
+
+#[test]
+fn wake_on_abandoned() {
+    let (mut p, mut c) = RingBuffer::<i32>::new(4, 0);
+    enable_parking(&mut p, &mut c);
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        drop(p);
+    });
+    let start = Instant::now();
+    assert_eq!(c.pop_blocking(LONG), Err(PopError::Empty));
+    assert!(start.elapsed() < LONG);
+    t.join().unwrap();
+}
+
+#[test]
+fn blocking_both_sides() {
+    const COUNT: u64 = 10_000;
+
+    let (mut p, mut c) = RingBuffer::new(4, 1);
+    enable_parking(&mut p, &mut c);
+    let t = thread::spawn(move || {
+        for i in 0..COUNT {
+            assert_eq!(p.push_blocking(i, LONG), Ok(i));
+        }
+    });
+    for i in 0..COUNT {
+        assert_eq!(c.pop_blocking(LONG), Ok(i));
+    }
+    t.join().unwrap();
+}
+
+#[test]
+fn wait_strategies() {
+    fn run<S: WaitStrategy + Clone + 'static>(strategy: S) {
+        const COUNT: u64 = 200;
+
+        let (mut p, mut c) = RingBuffer::new(2, 1);
+        enable_parking(&mut p, &mut c);
+        p.set_wait_strategy(strategy.clone());
+        c.set_wait_strategy(strategy);
+        let t = thread::spawn(move || {
+            for i in 0..COUNT {
+                assert_eq!(p.push_blocking(i, LONG), Ok(i));
+            }
+        });
+        for i in 0..COUNT {
+            assert_eq!(c.pop_blocking(LONG), Ok(i));
+        }
+        t.join().unwrap();
+        assert_eq!(
+            c.read_chunk_blocking(1, Duration::from_millis(5))
+                .unwrap_err(),
+            ChunkError::TooFewSlots(0)
+        );
+    }
+
+    run(BusySpin);
+    run(Yield);
+    run(ExponentialBackoff::default());
+    run(Park);
+}
+
+#[test]
+fn wait_without_parking() {
+    let (mut p, mut c) = RingBuffer::new(2, 0);
+    c.set_wait_strategy(Yield);
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        assert_eq!(p.push(10), Ok(0));
+    });
+    assert_eq!(c.pop_blocking(LONG), Ok(10));
+    t.join().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Parking has not been enabled")]
+fn park_without_enabling() {
+    let (_p, mut c) = RingBuffer::<i32>::new(2, 0);
+    let _ = c.pop_blocking(LONG);
+}
+
+#[test]
+#[should_panic(expected = "different ring buffers")]
+fn enable_parking_mismatch() {
+    let (mut p, _c) = RingBuffer::<i32>::new(2, 0);
+    let (_p, mut c) = RingBuffer::<i32>::new(2, 0);
+    enable_parking(&mut p, &mut c);
+}
+
+#[test]
+fn exponential_backoff() {
+    let mut backoff = ExponentialBackoff {
+        spins: 2,
+        yields: 1,
+        min_sleep: Duration::from_millis(1),
+        max_sleep: Duration::from_millis(5),
+    };
+    let steps: Vec<_> = (0..7).map(|i| backoff.step(i)).collect();
+    assert_eq!(
+        steps,
+        [
+            WaitStep::Spin,
+            WaitStep::Spin,
+            WaitStep::Yield,
+            WaitStep::Sleep(Duration::from_millis(1)),
+            WaitStep::Sleep(Duration::from_millis(2)),
+            WaitStep::Sleep(Duration::from_millis(4)),
+            WaitStep::Sleep(Duration::from_millis(5)),
+        ]
+    );
+    assert_eq!(
+        backoff.step(u32::max_value()),
+        WaitStep::Sleep(Duration::from_millis(5))
+    );
+}
@@ -0,0 +1,116 @@
+#![cfg(all(feature = "eventfd", target_os = "linux"))]
+
+use rtrb::RingBuffer;
+
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::thread;
+
+/// Returns `true` if `fd` is readable, waiting at most `timeout` milliseconds.
+fn readable(fd: RawFd, timeout: i32) -> bool {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let result = unsafe { libc::poll(&mut pollfd, 1, timeout) };
+    assert!(result >= 0);
+    result == 1
+}
+
+/// Returns the current counter value of the eventfd, without changing it.
+fn counter(fd: RawFd) -> u64 {
+    if !readable(fd, 0) {
+        return 0;
+    }
+    let mut value: u64 = 0;
+    let result = unsafe { libc::read(fd, &mut value as *mut u64 as *mut libc::c_void, 8) };
+    assert_eq!(result, 8);
+    let result = unsafe { libc::write(fd, &value as *const u64 as *const libc::c_void, 8) };
+    assert_eq!(result, 8);
+    value
+}
+
+#[test]
+fn consumer_eventfd() {
+    let (mut p, mut c) = RingBuffer::new(4, 0);
+    c.enable_eventfd().unwrap();
+    let fd = c.as_raw_fd();
+    c.enable_eventfd().unwrap();
+    assert_eq!(c.as_raw_fd(), fd);
+
+    // The eventfd is initially readable.
+    assert!(readable(fd, 0));
+    c.reset_eventfd().unwrap();
+    assert!(!readable(fd, 0));
+
+    // Signals are coalesced until the eventfd is reset.
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(p.push(20), Ok(1));
+    p.write_chunk_uninit(2)
+        .unwrap()
+        .fill_from_iter(vec![30, 40]);
+    assert_eq!(counter(fd), 1);
+    c.reset_eventfd().unwrap();
+    assert!(!readable(fd, 0));
+    assert_eq!(c.pop(), Ok(10));
+    assert!(!readable(fd, 0));
+
+    drop(p);
+    assert!(readable(fd, 0));
+}
+
+#[test]
+fn producer_eventfd() {
+    let (mut p, mut c) = RingBuffer::with_acks(2);
+    p.enable_eventfd().unwrap();
+    let fd = p.as_raw_fd();
+    p.reset_eventfd().unwrap();
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(p.push(20), Ok(1));
+    assert!(!readable(fd, 0));
+    assert_eq!(c.pop(), Ok(10));
+    assert!(readable(fd, 0));
+    p.reset_eventfd().unwrap();
+    assert!(!readable(fd, 0));
+    assert_eq!(c.ack(0), Ok(()));
+    assert!(readable(fd, 0));
+    assert_eq!(counter(fd), 1);
+}
+
+#[test]
+#[should_panic(expected = "Consumer::enable_eventfd() has not been called")]
+fn eventfd_not_enabled() {
+    let (_p, c) = RingBuffer::<u8>::new(4, 0);
+    let _ = c.as_raw_fd();
+}
+
+#[test]
+fn event_loop() {
+    const COUNT: u64 = 10_000;
+
+    let (mut p, mut c) = RingBuffer::new(8, 0);
+    p.enable_eventfd().unwrap();
+    c.enable_eventfd().unwrap();
+    let t = thread::spawn(move || {
+        let fd = p.as_raw_fd();
+        let mut i = 0;
+        while i < COUNT {
+            assert!(readable(fd, -1));
+            p.reset_eventfd().unwrap();
+            while i < COUNT && p.push(i).is_ok() {
+                i += 1;
+            }
+        }
+    });
+    let fd = c.as_raw_fd();
+    let mut expected = 0;
+    while expected < COUNT {
+        assert!(readable(fd, -1));
+        c.reset_eventfd().unwrap();
+        while let Ok(value) = c.pop() {
+            assert_eq!(value, expected);
+            expected += 1;
+        }
+    }
+    t.join().unwrap();
+}
@@ -0,0 +1,336 @@
+use rtrb::{chunks::ChunkError, OverflowPolicy, PeekError, PopError, PushError, RingBuffer};
+
+#[test]
+fn overwrite_oldest() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(3, 0, OverflowPolicy::OverwriteOldest);
+    for i in 0..5 {
+        assert_eq!(p.push(i), Ok(i as u64));
+    }
+    assert_eq!(p.buffer().overflowed(), 2);
+    assert_eq!(
+        c.pop(),
+        Err(PopError::Lagged {
+            first: 0,
+            skipped: 2
+        })
+    );
+    assert_eq!(c.head(), 2);
+    assert_eq!(c.pop_with_seq(), Ok((2, 2)));
+
+    // Unread items cannot be borrowed, which would stop the producer from overwriting.
+    assert_eq!(p.push(5), Ok(5));
+    assert_eq!(c.peek(), Err(PeekError::Overwritable));
+    assert!(c.history().is_empty());
+    assert_eq!(p.push(6), Ok(6));
+    assert_eq!(
+        c.pop(),
+        Err(PopError::Lagged {
+            first: 3,
+            skipped: 1
+        })
+    );
+    assert_eq!(c.pop_with_seq(), Ok((4, 4)));
+    assert_eq!(p.push(7), Ok(7));
+    assert_eq!(c.pop_with_seq(), Ok((5, 5)));
+    assert_eq!(c.pop_with_seq(), Ok((6, 6)));
+    assert_eq!(c.pop_with_seq(), Ok((7, 7)));
+    assert_eq!(c.pop(), Err(PopError::Empty));
+    assert_eq!(p.buffer().overflowed(), 3);
+    assert_eq!(c.lost_total(), 3);
+}
+
+#[test]
+fn overwrite_oldest_many_laps() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::OverwriteOldest);
+    for i in 0..10 {
+        assert_eq!(p.push(i), Ok(i as u64));
+    }
+    // The head is back at its original position.
+    assert_eq!(
+        c.pop(),
+        Err(PopError::Lagged {
+            first: 0,
+            skipped: 8
+        })
+    );
+    assert_eq!(c.pop(), Ok(8));
+    assert_eq!(c.pop(), Ok(9));
+    assert_eq!(c.pop(), Err(PopError::Empty));
+}
+
+#[test]
+fn overwrite_oldest_after_queries() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::OverwriteOldest);
+    // Queries on an empty queue don't stop the producer from overwriting.
+    assert_eq!(c.slots(), 0);
+    assert!(c.is_empty());
+    assert_eq!(c.pop(), Err(PopError::Empty));
+    assert_eq!(c.peek(), Err(PeekError::Empty));
+    for i in 0..3 {
+        assert_eq!(p.push(i), Ok(i as u64));
+    }
+    // Neither do queries on a full queue.
+    assert_eq!(c.slots(), 2);
+    assert!(!c.is_empty());
+    assert_eq!(c.lost_total(), 1);
+    assert_eq!(p.push(3), Ok(3));
+    assert_eq!(c.slots(), 2);
+    assert_eq!(c.lost_total(), 2);
+    assert_eq!(
+        c.pop(),
+        Err(PopError::Lagged {
+            first: 0,
+            skipped: 2
+        })
+    );
+    assert_eq!(c.lost_total(), 2);
+    assert_eq!(c.pop(), Ok(2));
+    assert_eq!(c.pop(), Ok(3));
+}
+
+#[test]
+fn overwrite_oldest_read_chunk() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(4, 0, OverflowPolicy::OverwriteOldest);
+    for i in 0..6 {
+        assert_eq!(p.push(i), Ok(i as u64));
+    }
+    assert_eq!(
+        c.read_chunk(3).unwrap_err(),
+        ChunkError::Lagged {
+            first: 0,
+            skipped: 2
+        }
+    );
+    let chunk = c.read_chunk(3).unwrap();
+    assert_eq!(chunk.as_slices(), (&[2, 3][..], &[4][..]));
+    assert_eq!(p.push(6), Err(PushError::Full(6)));
+    chunk.commit_all();
+    assert_eq!(p.push(6), Ok(6));
+    assert_eq!(c.pop_with_seq(), Ok((5, 5)));
+    assert_eq!(c.pop_with_seq(), Ok((6, 6)));
+    assert_eq!(c.peek(), Err(PeekError::Empty));
+}
+
+#[test]
+fn drop_newest() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(3, 1, OverflowPolicy::DropNewest);
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(p.push(20), Ok(1));
+    assert_eq!(p.push(30), Err(PushError::Dropped));
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(p.push(40), Ok(2));
+    assert_eq!(p.push(50), Err(PushError::Dropped));
+    assert_eq!(c.buffer().overflowed(), 2);
+    assert_eq!(c.pop_with_seq(), Ok((1, 20)));
+    assert_eq!(c.pop_with_seq(), Ok((2, 40)));
+    assert_eq!(c.pop(), Err(PopError::Empty));
+}
+
+#[test]
+#[should_panic(expected = "Resend window is not supported when overwriting the oldest items")]
+fn overwrite_oldest_with_resend_window() {
+    let _ = RingBuffer::<i32>::with_overflow_policy(3, 1, OverflowPolicy::OverwriteOldest);
+}
+
+#[test]
+fn overwrite_oldest_concurrently() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct Item(u64);
+
+    impl Drop for Item {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    const ITEMS: u64 = 100_000;
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(8, 0, OverflowPolicy::OverwriteOldest);
+
+    let pushing = std::thread::spawn(move || {
+        for i in 0..ITEMS {
+            let mut item = Item(i);
+            loop {
+                match p.push(item) {
+                    Ok(seq) => {
+                        assert_eq!(seq, i);
+                        break;
+                    }
+                    // The consumer is reading the oldest item.
+                    Err(PushError::Full(returned)) => {
+                        item = returned;
+                        std::thread::yield_now();
+                    }
+                    Err(e) => panic!("unexpected error: {}", e),
+                }
+            }
+        }
+    });
+
+    let mut popped = 0;
+    let mut skipped = 0;
+    loop {
+        match c.pop_with_seq() {
+            Ok((seq, item)) => {
+                assert_eq!(seq, item.0);
+                assert_eq!(seq, popped + skipped);
+                popped += 1;
+            }
+            Err(PopError::Lagged { first, skipped: n }) => {
+                assert_eq!(first, popped + skipped);
+                skipped += n;
+            }
+            Err(PopError::Empty) => {
+                if c.is_abandoned() && c.is_empty() {
+                    break;
+                }
+                std::thread::yield_now();
+            }
+        }
+    }
+    pushing.join().unwrap();
+    assert_eq!(popped + skipped, ITEMS);
+    assert_eq!(c.buffer().overflowed() as u64, skipped);
+    assert_eq!(c.lost_total(), skipped);
+    assert_eq!(DROPS.load(Ordering::Relaxed) as u64, ITEMS);
+}
+
+#[test]
+fn overwrite_oldest_capacity_one() {
+    const ITEMS: u64 = if cfg!(miri) { 1_000 } else { 100_000 };
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(1, 0, OverflowPolicy::OverwriteOldest);
+
+    let pushing = std::thread::spawn(move || {
+        for i in 0..ITEMS {
+            let mut item = i.to_string();
+            loop {
+                match p.push(item) {
+                    Ok(_) => break,
+                    Err(PushError::Full(returned)) => item = returned,
+                    Err(e) => panic!("unexpected error: {}", e),
+                }
+            }
+        }
+    });
+
+    let mut next = 0;
+    while next < ITEMS {
+        match c.pop_with_seq() {
+            Ok((seq, item)) => {
+                assert_eq!(item, seq.to_string());
+                next = seq + 1;
+            }
+            Err(PopError::Lagged { first, skipped }) => {
+                assert_eq!(first, next);
+                next += skipped;
+            }
+            // The last item cannot be overwritten, it will show up eventually.
+            Err(PopError::Empty) => {}
+        }
+    }
+    pushing.join().unwrap();
+    assert_eq!(c.head(), ITEMS);
+    assert!(c.is_empty());
+}
+
+#[test]
+fn overwrite_oldest_transaction() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::OverwriteOldest);
+    for i in 0..3 {
+        assert_eq!(p.push(i), Ok(i as u64));
+    }
+    let mut tx = c.begin();
+    // Items popped in the transaction are protected from being overwritten.
+    assert_eq!(p.push(3), Err(PushError::Full(3)));
+    assert_eq!(
+        tx.pop(),
+        Err(PopError::Lagged {
+            first: 0,
+            skipped: 1
+        })
+    );
+    assert_eq!(tx.pop(), Ok(1));
+    tx.rollback();
+
+    // The lag is reported again after a rollback.
+    let mut tx = c.begin();
+    assert_eq!(
+        tx.pop(),
+        Err(PopError::Lagged {
+            first: 0,
+            skipped: 1
+        })
+    );
+    assert_eq!(tx.pop_with_seq(), Ok((1, 1)));
+    tx.commit();
+    assert_eq!(p.push(3), Ok(3));
+    assert_eq!(p.push(4), Ok(4));
+    assert_eq!(
+        c.pop(),
+        Err(PopError::Lagged {
+            first: 2,
+            skipped: 1
+        })
+    );
+    assert_eq!(c.pop(), Ok(3));
+    assert_eq!(c.pop(), Ok(4));
+}
+
+#[test]
+fn overwrite_oldest_after_failed_read_chunk() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::OverwriteOldest);
+    assert_eq!(p.push(0), Ok(0));
+    assert_eq!(c.read_chunk(2).unwrap_err(), ChunkError::TooFewSlots(1));
+    assert_eq!(p.push(1), Ok(1));
+    assert_eq!(p.push(2), Ok(2));
+    assert_eq!(
+        c.read_chunk(2).unwrap_err(),
+        ChunkError::Lagged {
+            first: 0,
+            skipped: 1
+        }
+    );
+    // An empty chunk doesn't protect the items.
+    assert!(c.read_chunk(0).unwrap().is_empty());
+    assert_eq!(p.push(3), Ok(3));
+    assert_eq!(p.push(4), Ok(4));
+    assert_eq!(
+        c.pop(),
+        Err(PopError::Lagged {
+            first: 1,
+            skipped: 2
+        })
+    );
+    assert_eq!(c.pop_with_seq(), Ok((3, 3)));
+    assert_eq!(c.pop_with_seq(), Ok((4, 4)));
+}
+
+#[test]
+fn overwrite_oldest_after_rollback() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::OverwriteOldest);
+    assert_eq!(p.push(0), Ok(0));
+    assert_eq!(p.push(1), Ok(1));
+    let mut tx = c.begin();
+    assert_eq!(tx.pop(), Ok(0));
+    assert_eq!(p.push(2), Err(PushError::Full(2)));
+    tx.rollback();
+    assert_eq!(p.push(2), Ok(2));
+    {
+        let tx = c.begin();
+        assert_eq!(tx.peek(), Ok(&1));
+        assert_eq!(p.push(3), Err(PushError::Full(3)));
+    }
+    assert_eq!(p.push(3), Ok(3));
+    assert_eq!(
+        c.pop(),
+        Err(PopError::Lagged {
+            first: 0,
+            skipped: 2
+        })
+    );
+    assert_eq!(c.pop(), Ok(2));
+    assert_eq!(c.pop(), Ok(3));
+}
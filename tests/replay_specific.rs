@@ -1,4 +1,9 @@
-use rtrb::RingBuffer;
+use std::ops::Bound;
+
+use rtrb::{
+    chunks::ChunkError, AckError, Consumer, HistoryError, HistorySnapshot, PopError, PushError,
+    RewindError, RingBuffer,
+};
 
 #[test]
 fn resend_window_blocking() {
@@ -29,9 +34,9 @@ fn history_basic_access() {
     
     // Check history contains all items
     assert_eq!(history.len(), 3);
-    assert_eq!(history.get(history.start_index()), Some(&10));
-    assert_eq!(history.get(history.start_index() + 1), Some(&20));
-    assert_eq!(history.get(history.start_index() + 2), Some(&30));
+    assert_eq!(history.get(history.start_index()), Ok(&10));
+    assert_eq!(history.get(history.start_index() + 1), Ok(&20));
+    assert_eq!(history.get(history.start_index() + 2), Ok(&30));
 }
 
 #[test]
@@ -49,8 +54,8 @@ fn history_after_pop() {
     
     // Check history excludes popped item
     assert_eq!(history.len(), 2);
-    assert_eq!(history.get(history.start_index()), Some(&20));
-    assert_eq!(history.get(history.start_index() + 1), Some(&30));
+    assert_eq!(history.get(history.start_index()), Ok(&20));
+    assert_eq!(history.get(history.start_index() + 1), Ok(&30));
 }
 
 #[test]
@@ -91,9 +96,9 @@ fn history_wraparound() {
     
     // Check history contains all items
     assert_eq!(history.len(), 3);
-    assert_eq!(history.get(history.start_index()), Some(&30));
-    assert_eq!(history.get(history.start_index() + 1), Some(&40));
-    assert_eq!(history.get(history.start_index() + 2), Some(&50));
+    assert_eq!(history.get(history.start_index()), Ok(&30));
+    assert_eq!(history.get(history.start_index() + 1), Ok(&40));
+    assert_eq!(history.get(history.start_index() + 2), Ok(&50));
 }
  #[test]
 fn history_iteration() {
@@ -131,9 +136,9 @@ fn history_with_resend_window() {
     
     // History should contain all written messages
     assert_eq!(history.len(), 3);
-    assert_eq!(history.get(history.start_index()), Some(&10));
-    assert_eq!(history.get(history.start_index() + 1), Some(&20));
-    assert_eq!(history.get(history.start_index() + 2), Some(&30));
+    assert_eq!(history.get(history.start_index()), Ok(&10));
+    assert_eq!(history.get(history.start_index() + 1), Ok(&20));
+    assert_eq!(history.get(history.start_index() + 2), Ok(&30));
     
     assert_eq!(c.pop().unwrap(), 10);
     p.push(40).unwrap();
@@ -142,9 +147,9 @@ fn history_with_resend_window() {
     
     // History should reflect new state
     assert_eq!(history.len(), 3);
-    assert_eq!(history.get(history.start_index()), Some(&20));
-    assert_eq!(history.get(history.start_index() + 1), Some(&30));
-    assert_eq!(history.get(history.start_index() + 2), Some(&40));
+    assert_eq!(history.get(history.start_index()), Ok(&20));
+    assert_eq!(history.get(history.start_index() + 1), Ok(&30));
+    assert_eq!(history.get(history.start_index() + 2), Ok(&40));
 }
 
 #[test]
@@ -164,9 +169,9 @@ fn history_indexing() {
     assert_eq!(history.end_index(), initial_head + 3);
     
     // Check items at specific indices
-    assert_eq!(history.get(initial_head), None);
-    assert_eq!(history.get(initial_head + 1), Some(&20));
-    assert_eq!(history.get(initial_head + 2), Some(&30));
+    assert_eq!(history.get(initial_head), Err(HistoryError::Expired));
+    assert_eq!(history.get(initial_head + 1), Ok(&20));
+    assert_eq!(history.get(initial_head + 2), Ok(&30));
 }
 
 #[test]
@@ -209,11 +214,896 @@ fn history_position_access() {
     
     // Access messages by their specific indices
     let history = c.history();
-    assert_eq!(history.get(start_pos), Some(&100));
-    assert_eq!(history.get(start_pos + 1), Some(&200));
+    assert_eq!(history.get(start_pos), Ok(&100));
+    assert_eq!(history.get(start_pos + 1), Ok(&200));
     
     c.pop().unwrap();
     let history = c.history();
-    assert_eq!(history.get(start_pos), None);
-    assert_eq!(history.get(start_pos + 1), Some(&200));
-}
\ No newline at end of file
+    assert_eq!(history.get(start_pos), Err(HistoryError::Expired));
+    assert_eq!(history.get(start_pos + 1), Ok(&200));
+}
+#[test]
+fn rewind_pop() {
+    let (mut p, mut c) = RingBuffer::with_replay(5, 2);
+
+    p.push(10).unwrap();
+    p.push(20).unwrap();
+    p.push(30).unwrap();
+
+    assert_eq!(c.retained(), 0);
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(c.pop(), Ok(20));
+    assert_eq!(c.pop(), Ok(30));
+    // Only the last two items fit into the resend window
+    assert_eq!(c.retained(), 2);
+    assert!(c.is_empty());
+
+    c.rewind(2).unwrap();
+    assert_eq!(c.retained(), 0);
+    assert_eq!(c.slots(), 2);
+    assert_eq!(c.peek(), Ok(&20));
+    assert_eq!(c.pop(), Ok(20));
+    assert_eq!(c.pop(), Ok(30));
+    assert!(c.pop().is_err());
+    assert_eq!(c.retained(), 2);
+}
+
+#[test]
+fn rewind_too_far() {
+    let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+
+    assert_eq!(c.rewind(0), Ok(()));
+    assert_eq!(c.rewind(1), Err(RewindError::TooFewItems(0)));
+
+    p.push(10).unwrap();
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(c.rewind(2), Err(RewindError::TooFewItems(1)));
+    assert_eq!(
+        c.rewind(2).unwrap_err().to_string(),
+        "only 1 items retained in ring buffer"
+    );
+    assert_eq!(c.rewind(1), Ok(()));
+    assert_eq!(c.pop(), Ok(10));
+}
+
+#[test]
+fn rewind_read_chunk() {
+    let (mut p, mut c) = RingBuffer::with_replay(5, 2);
+
+    // Wrap around a few times
+    for i in 0..10 {
+        p.push(i).unwrap();
+        assert_eq!(c.pop(), Ok(i));
+    }
+    c.rewind(2).unwrap();
+    p.push(10).unwrap();
+
+    let chunk = c.read_chunk(3).unwrap();
+    let (first, second) = chunk.as_slices();
+    let items: Vec<_> = first.iter().chain(second).copied().collect();
+    assert_eq!(items, [8, 9, 10]);
+    chunk.commit(1);
+    // Committed items are dropped, nothing is retained anymore
+    assert_eq!(c.retained(), 0);
+    assert_eq!(c.pop(), Ok(9));
+    assert_eq!(c.retained(), 1);
+}
+
+#[test]
+fn rewind_without_clone() {
+    let (mut p, mut c) = RingBuffer::new(4, 2);
+
+    p.push(10).unwrap();
+    assert_eq!(c.pop(), Ok(10));
+    // The item has been moved out, it cannot be retained
+    assert_eq!(c.retained(), 0);
+    assert!(c.rewind(1).is_err());
+}
+
+#[test]
+fn retained_items_are_dropped() {
+    use std::rc::Rc;
+
+    let item = Rc::new(());
+    {
+        let (mut p, mut c) = RingBuffer::with_replay(6, 3);
+        for _ in 0..3 {
+            p.push(Rc::clone(&item)).unwrap();
+        }
+        for _ in 0..2 {
+            drop(c.pop().unwrap());
+        }
+        // Two retained, one pending
+        assert_eq!(Rc::strong_count(&item), 4);
+        drop(c.pop().unwrap());
+        p.push(Rc::clone(&item)).unwrap();
+        p.push(Rc::clone(&item)).unwrap();
+        drop(c.pop().unwrap());
+        // The oldest item has fallen out of the resend window
+        assert_eq!(Rc::strong_count(&item), 5);
+        c.rewind(2).unwrap();
+        assert_eq!(Rc::strong_count(&item), 5);
+    }
+    assert_eq!(Rc::strong_count(&item), 1);
+}
+
+#[test]
+fn sequence_numbers() {
+    let (mut p, mut c) = RingBuffer::new(4, 0);
+
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(p.push(20), Ok(1));
+    assert_eq!(c.pop_with_seq(), Ok((0, 10)));
+    assert_eq!(c.head(), 1);
+
+    // Chunks advance the sequence numbers as well
+    p.write_chunk_uninit(2).unwrap().fill_from_iter([30, 40]);
+    assert_eq!(p.push(50), Ok(4));
+    c.read_chunk(2).unwrap().commit_all();
+    assert_eq!(c.head(), 3);
+    assert_eq!(c.pop_with_seq(), Ok((3, 40)));
+    assert_eq!(c.pop_with_seq(), Ok((4, 50)));
+    assert!(c.pop_with_seq().is_err());
+}
+
+#[test]
+fn history_sequence_across_wraparound() {
+    let (mut p, mut c) = RingBuffer::new(3, 0);
+
+    for i in 0..7 {
+        p.push(i).unwrap();
+        c.pop().unwrap();
+    }
+    // The raw positions wrap around in the middle of this window
+    p.push(7).unwrap();
+    p.push(8).unwrap();
+    p.push(9).unwrap();
+
+    let history = c.history();
+    assert_eq!(history.start_index(), 7);
+    assert_eq!(history.end_index(), 10);
+    assert_eq!(history.get(6), Err(HistoryError::Expired));
+    assert_eq!(history.get(7), Ok(&7));
+    assert_eq!(history.get(8), Ok(&8));
+    assert_eq!(history.get(9), Ok(&9));
+    assert_eq!(history.get(10), Err(HistoryError::NotYetWritten));
+    // An older sequence number which maps to the same slot is not aliased
+    assert_eq!(history.get(1), Err(HistoryError::Expired));
+    assert_eq!(history.iter().copied().collect::<Vec<_>>(), [7, 8, 9]);
+}
+
+#[test]
+fn history_contains_retained_items() {
+    let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+
+    for i in 0..5 {
+        p.push(i).unwrap();
+        c.pop().unwrap();
+    }
+    p.push(5).unwrap();
+
+    let history = c.history();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.start_index(), 3);
+    assert_eq!(history.get(2), Err(HistoryError::Expired));
+    assert_eq!(history.get(3), Ok(&3));
+    assert_eq!(history.get(5), Ok(&5));
+    assert_eq!(
+        history.get(2).unwrap_err().to_string(),
+        "item expired from ring buffer"
+    );
+
+    c.rewind(1).unwrap();
+    assert_eq!(c.head(), 4);
+    assert_eq!(c.pop_with_seq(), Ok((4, 4)));
+}
+
+#[test]
+fn write_chunk_respects_resend_window() {
+    let (mut p, mut c) = RingBuffer::with_replay(5, 2);
+
+    assert_eq!(p.slots(), 3);
+    assert_eq!(
+        p.write_chunk(4).unwrap_err(),
+        ChunkError::ResendWindowFull {
+            usable: 3,
+            retained: 0
+        }
+    );
+    p.write_chunk_uninit(3)
+        .unwrap()
+        .fill_from_iter([10, 20, 30]);
+    assert_eq!(p.slots(), 0);
+    assert_eq!(
+        p.write_chunk(1).unwrap_err(),
+        ChunkError::ResendWindowFull {
+            usable: 0,
+            retained: 0
+        }
+    );
+    assert_eq!(p.write_chunk(3).unwrap_err(), ChunkError::TooFewSlots(0));
+
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(c.pop(), Ok(20));
+    assert_eq!(p.slots(), 2);
+    p.write_chunk_uninit(2).unwrap().fill_from_iter([40, 50]);
+
+    // The retained items have not been overwritten
+    c.rewind(2).unwrap();
+    assert_eq!(p.slots(), 0);
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(c.pop(), Ok(20));
+    assert_eq!(c.pop(), Ok(30));
+}
+
+#[test]
+fn window_full_and_buffer_full() {
+    let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(p.push(20), Ok(1));
+    assert_eq!(p.push(30), Err(PushError::WindowFull(30)));
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(c.pop(), Ok(20));
+    assert_eq!(p.push(30), Ok(2));
+    assert_eq!(p.push(40), Ok(3));
+    assert_eq!(p.push(50), Err(PushError::Full(50)));
+    assert_eq!(p.write_chunk(1).unwrap_err(), ChunkError::TooFewSlots(0));
+
+    assert_eq!(c.pop(), Ok(30));
+    assert_eq!(p.push(50), Ok(4));
+    assert_eq!(p.push(60), Err(PushError::Full(60)));
+
+    // Only one item is retained, the other protected slot is still free
+    let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(p.push(20), Ok(1));
+    assert_eq!(p.push(30), Ok(2));
+    assert_eq!(c.retained(), 1);
+    assert_eq!(p.push(40), Err(PushError::WindowFull(40)));
+    assert_eq!(
+        p.write_chunk(1).unwrap_err(),
+        ChunkError::ResendWindowFull {
+            usable: 0,
+            retained: 1
+        }
+    );
+    assert_eq!(p.write_chunk(2).unwrap_err(), ChunkError::TooFewSlots(0));
+    assert_eq!(
+        PushError::WindowFull(50).to_string(),
+        "full resend window in ring buffer"
+    );
+}
+
+#[test]
+fn acks_protect_consumed_items() {
+    let (mut p, mut c) = RingBuffer::with_acks(3);
+
+    assert_eq!(p.max_advance(), 3);
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(p.push(20), Ok(1));
+    assert_eq!(p.push(30), Ok(2));
+    assert_eq!(p.push(40), Err(PushError::Full(40)));
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(c.pop(), Ok(20));
+    assert_eq!(c.unacked().len(), 2);
+    assert_eq!(p.push(40), Err(PushError::WindowFull(40)));
+    assert_eq!(
+        p.write_chunk(2).unwrap_err(),
+        ChunkError::ResendWindowFull {
+            usable: 0,
+            retained: 2
+        }
+    );
+
+    // Nothing is released before the acknowledgement
+    c.rewind(2).unwrap();
+    assert_eq!(c.pop_with_seq(), Ok((0, 10)));
+    assert_eq!(c.pop_with_seq(), Ok((1, 20)));
+    assert_eq!(c.pop_with_seq(), Ok((2, 30)));
+    assert_eq!(c.ack(3), Err(AckError::NotConsumed));
+
+    assert_eq!(c.ack(1), Ok(()));
+    assert_eq!(c.retained(), 1);
+    assert_eq!(c.unacked().get(2), Ok(&30));
+    assert_eq!(c.rewind(2), Err(RewindError::TooFewItems(1)));
+    assert_eq!(p.slots(), 2);
+    assert_eq!(p.push(40), Ok(3));
+    assert_eq!(p.push(50), Ok(4));
+    assert_eq!(p.push(60), Err(PushError::WindowFull(60)));
+
+    c.rewind(1).unwrap();
+    assert_eq!(c.pop(), Ok(30));
+    assert_eq!(c.ack(2), Ok(()));
+    assert_eq!(p.push(60), Ok(5));
+}
+
+#[test]
+fn acked_items_are_dropped() {
+    use std::rc::Rc;
+
+    let counter = Rc::new(());
+    {
+        let (mut p, mut c) = RingBuffer::with_acks(4);
+        for _ in 0..4 {
+            p.push(Rc::clone(&counter)).unwrap();
+        }
+        c.pop().unwrap();
+        c.pop().unwrap();
+        assert_eq!(Rc::strong_count(&counter), 5);
+        c.ack(0).unwrap();
+        assert_eq!(Rc::strong_count(&counter), 4);
+
+        // Committing a chunk acknowledges all previous items
+        c.read_chunk(1).unwrap().commit_all();
+        assert_eq!(Rc::strong_count(&counter), 2);
+        assert_eq!(c.retained(), 0);
+        assert_eq!(p.slots(), 3);
+    }
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+fn selective_acks() {
+    use std::rc::Rc;
+
+    fn unacked<T>(c: &Consumer<T>) -> Vec<(u64, u64)> {
+        c.unacked_ranges().map(|r| (r.start, r.end)).collect()
+    }
+
+    let counter = Rc::new(());
+    let (mut p, mut c) = RingBuffer::with_acks(5);
+    for i in 0..5 {
+        assert_eq!(p.push((i, Rc::clone(&counter))).map_err(|_| ()), Ok(i));
+    }
+    for _ in 0..4 {
+        c.pop().unwrap();
+    }
+    assert_eq!(unacked(&c), [(0, 4)]);
+    assert_eq!(c.selective_ack(4), Err(AckError::NotConsumed));
+    c.selective_ack(2).unwrap();
+    c.selective_ack(1).unwrap();
+    assert_eq!(unacked(&c), [(0, 1), (3, 4)]);
+    assert_eq!(c.retained(), 4);
+    assert_eq!(Rc::strong_count(&counter), 6);
+
+    // Acknowledged items are still re-delivered until they are released
+    c.rewind(3).unwrap();
+    assert_eq!(c.pop().unwrap().0, 1);
+    assert_eq!(unacked(&c), [(0, 1)]);
+
+    // The contiguous run of acknowledged items is released
+    c.ack(0).unwrap();
+    assert_eq!(c.retained(), 0);
+    assert_eq!(unacked(&c), []);
+    assert_eq!(p.slots(), 2);
+    assert_eq!(Rc::strong_count(&counter), 4);
+
+    // Item 2 has already been acknowledged, it is released right away
+    assert_eq!(c.pop().unwrap().0, 2);
+    assert_eq!(c.retained(), 0);
+    assert_eq!(c.pop().unwrap().0, 3);
+    assert_eq!(c.pop().unwrap().0, 4);
+    assert_eq!(unacked(&c), [(3, 5)]);
+    c.selective_ack(4).unwrap();
+    assert_eq!(unacked(&c), [(3, 4)]);
+
+    // Skipping acknowledged items with a chunk clears their acknowledgement
+    c.rewind(2).unwrap();
+    c.read_chunk(2).unwrap().commit_all();
+    assert_eq!(c.retained(), 0);
+    assert_eq!(Rc::strong_count(&counter), 1);
+    for i in 5..10 {
+        assert_eq!(p.push((i, Rc::clone(&counter))).map_err(|_| ()), Ok(i));
+    }
+    for i in 5..10 {
+        assert_eq!(c.pop().unwrap().0, i);
+    }
+    assert_eq!(unacked(&c), [(5, 10)]);
+}
+
+#[test]
+fn producer_history() {
+    let (mut p, mut c) = RingBuffer::with_replay(5, 2);
+
+    assert!(p.history().is_empty());
+    for i in 0..3 {
+        assert_eq!(p.push(i * 10), Ok(i));
+    }
+    // Unread items are not available.
+    assert!(p.history().is_empty());
+    assert_eq!(p.history().start_index(), 0);
+    assert_eq!(c.pop(), Ok(0));
+    assert_eq!(p.history().iter().collect::<Vec<_>>(), [&0]);
+    for i in 1..3 {
+        assert_eq!(c.pop(), Ok(i * 10));
+    }
+    let history = p.history();
+    assert_eq!(history.start_index(), 1);
+    assert_eq!(history.end_index(), 3);
+    assert_eq!(history.get(0), Err(HistoryError::Expired));
+    assert_eq!(history.get(3), Err(HistoryError::NotYetWritten));
+
+    for i in 3..5 {
+        assert_eq!(p.push(i * 10), Ok(i));
+    }
+    let history = p.history();
+    assert_eq!(history.start_index(), 1);
+    assert_eq!(history.iter().collect::<Vec<_>>(), [&10, &20]);
+    assert_eq!(history.get(3), Err(HistoryError::NotYetWritten));
+
+    // Without a resend window, no items are available
+    let (mut p, mut c) = RingBuffer::new(3, 0);
+    for i in 0..5 {
+        assert_eq!(p.push(i), Ok(i as u64));
+        assert_eq!(c.pop(), Ok(i));
+    }
+    assert_eq!(p.push(5), Ok(5));
+    let history = p.history();
+    assert!(history.is_empty());
+    assert_eq!(history.start_index(), 5);
+}
+
+#[test]
+fn producer_history_while_consumer_modifies() {
+    let (mut p, mut c) = RingBuffer::with_replay(6, 2);
+    for i in 0..4 {
+        assert_eq!(p.push(i * 10), Ok(i));
+    }
+    assert_eq!(c.pop(), Ok(0));
+    assert_eq!(c.pop(), Ok(10));
+    let history = p.history();
+    let t = std::thread::spawn(move || {
+        // Only unread items can be modified, the producer cannot access them.
+        let mut chunk = c.read_chunk(2).unwrap();
+        for item in chunk.as_mut_slices().0 {
+            *item += 1;
+        }
+        chunk.commit_retaining(2);
+        c
+    });
+    assert_eq!(history.iter().collect::<Vec<_>>(), [&0, &10]);
+    let mut c = t.join().unwrap();
+    assert_eq!(p.history().iter().collect::<Vec<_>>(), [&21, &31]);
+    c.rewind(2).unwrap();
+    assert_eq!(c.pop(), Ok(21));
+}
+
+#[test]
+#[should_panic(expected = "rewound items cannot be accessed mutably")]
+fn rewound_items_are_not_mutable() {
+    let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(p.push(20), Ok(1));
+    assert_eq!(c.pop(), Ok(10));
+    c.rewind(1).unwrap();
+    let _ = c.read_chunk(2).unwrap().as_mut_slices();
+}
+
+#[test]
+fn read_chunk_commit_retaining() {
+    use std::rc::Rc;
+
+    let counter = Rc::new(());
+    {
+        let (mut p, mut c) = RingBuffer::new(6, 3);
+        for i in 0..3 {
+            assert_eq!(p.push((i, Rc::clone(&counter))).map_err(|_| ()), Ok(i));
+        }
+        let chunk = c.read_chunk(3).unwrap();
+        assert_eq!(chunk.iter().map(|x| x.0).collect::<Vec<_>>(), [0, 1, 2]);
+        chunk.commit_retaining(2);
+        assert_eq!(c.retained(), 2);
+        assert_eq!(Rc::strong_count(&counter), 4);
+
+        // The clones are dropped, the originals stay in the ring buffer
+        let v: Vec<_> = c.read_chunk(1).unwrap().into_iter_retaining().collect();
+        assert_eq!(v[0].0, 2);
+        drop(v);
+        assert_eq!(Rc::strong_count(&counter), 4);
+        assert_eq!(c.retained(), 3);
+
+        for i in 3..6 {
+            assert_eq!(p.push((i, Rc::clone(&counter))).map_err(|_| ()), Ok(i));
+        }
+        // Items falling out of the resend window are dropped
+        c.read_chunk(2).unwrap().commit_retaining(2);
+        assert_eq!(c.retained(), 3);
+        assert_eq!(Rc::strong_count(&counter), 5);
+
+        c.rewind(3).unwrap();
+        let seqs: Vec<_> = c.history().iter().map(|x| x.0).collect();
+        assert_eq!(seqs, [2, 3, 4, 5]);
+        assert_eq!(c.pop_with_seq().unwrap().0, 2);
+    }
+    assert_eq!(Rc::strong_count(&counter), 1);
+
+    // Without a resend window, the items are dropped
+    let (mut p, mut c) = RingBuffer::new(2, 0);
+    assert_eq!(p.push(Rc::clone(&counter)).map_err(|_| ()), Ok(0));
+    c.read_chunk(1).unwrap().commit_retaining(1);
+    assert_eq!(Rc::strong_count(&counter), 1);
+    assert_eq!(c.retained(), 0);
+}
+
+#[test]
+fn set_resend_window() {
+    let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    for i in 0..2 {
+        assert_eq!(p.push(i), Ok(i));
+        assert_eq!(c.pop(), Ok(i));
+    }
+    assert_eq!(c.retained(), 2);
+
+    // Shrinking releases items immediately
+    c.set_resend_window(0);
+    assert_eq!(c.buffer().resend_window(), 0);
+    assert_eq!(c.retained(), 0);
+    assert_eq!(p.slots(), 4);
+    for i in 2..6 {
+        assert_eq!(p.push(i), Ok(i));
+    }
+
+    // Growing takes effect as the consumer advances
+    c.set_resend_window(3);
+    assert_eq!(c.retained(), 0);
+    assert_eq!(p.slots(), 0);
+    assert_eq!(c.pop(), Ok(2));
+    assert_eq!(p.slots(), 0);
+    assert_eq!(c.pop(), Ok(3));
+    assert_eq!(c.pop(), Ok(4));
+    assert_eq!(c.retained(), 3);
+    assert_eq!(p.slots(), 0);
+    assert_eq!(c.pop(), Ok(5));
+    assert_eq!(c.retained(), 3);
+    assert_eq!(p.slots(), 1);
+    assert_eq!(p.push(6), Ok(6));
+
+    c.rewind(3).unwrap();
+    for i in 3..7 {
+        assert_eq!(c.pop(), Ok(i));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Resend window cannot exceed capacity")]
+fn set_resend_window_too_large() {
+    let (_p, mut c) = RingBuffer::<i32>::new(4, 2);
+    c.set_resend_window(5);
+}
+
+#[test]
+fn set_resend_window_concurrently() {
+    const COUNT: u64 = 100_000;
+
+    let (mut p, mut c) = RingBuffer::with_replay(8, 2);
+    let pt = std::thread::spawn(move || {
+        let mut next = 0;
+        while next < COUNT {
+            if p.push(next).is_ok() {
+                next += 1;
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    });
+    let mut expected = 0;
+    let mut pops = 0_usize;
+    while expected < COUNT {
+        if let Ok((seq, value)) = c.pop_with_seq() {
+            assert_eq!(seq, value);
+            assert_eq!(value, expected);
+            expected += 1;
+            pops += 1;
+            if pops % 7 == 0 {
+                c.set_resend_window(pops % 8);
+            }
+            if pops % 5 == 0 {
+                let n = c.retained().min(3);
+                c.rewind(n).unwrap();
+                expected -= n as u64;
+                let history = c.history();
+                for (seq, item) in (history.start_index()..).zip(history.iter()) {
+                    assert_eq!(seq, *item);
+                }
+            }
+        } else {
+            std::thread::yield_now();
+        }
+    }
+    pt.join().unwrap();
+}
+
+#[test]
+fn history_snapshot() {
+    let (mut p, mut c) = RingBuffer::with_replay(6, 2);
+    for i in 0..4 {
+        assert_eq!(p.push(i * 10), Ok(i));
+    }
+    assert_eq!(c.pop(), Ok(0));
+    assert_eq!(c.pop(), Ok(10));
+
+    let snapshot = c.history().snapshot();
+    assert_eq!(snapshot.len(), 4);
+    assert_eq!(snapshot.start_index(), 0);
+    assert_eq!(snapshot.end_index(), 4);
+    assert_eq!(snapshot.get(1), Ok(&10));
+    assert_eq!(snapshot.get(4), Err(HistoryError::NotYetWritten));
+
+    // The snapshot is not affected by consuming more items.
+    assert_eq!(c.pop(), Ok(20));
+    assert_eq!(c.history().get(0), Err(HistoryError::Expired));
+    let snapshot = std::thread::spawn(move || {
+        assert_eq!(
+            snapshot.iter().copied().collect::<Vec<_>>(),
+            [0, 10, 20, 30]
+        );
+        snapshot
+    })
+    .join()
+    .unwrap();
+
+    // Re-using the allocation keeps only the most recent items.
+    let mut bounded = HistorySnapshot::with_capacity(2);
+    let capacity = bounded.capacity();
+    c.history().snapshot_into(&mut bounded);
+    assert_eq!(bounded.capacity(), capacity);
+    assert_eq!(bounded.end_index(), 4);
+    assert_eq!(bounded.len(), capacity.min(3));
+    assert_eq!(bounded.get(0), Err(HistoryError::Expired));
+    assert_eq!(bounded.as_slice().last(), Some(&30));
+    assert_eq!(snapshot.into_vec(), [0, 10, 20, 30]);
+}
+
+#[test]
+fn history_reader_concurrently() {
+    const COUNT: u64 = 20_000;
+
+    let (mut p, mut c, reader) = RingBuffer::with_history_reader(8, 2);
+    let pt = std::thread::spawn(move || {
+        let mut next = 0;
+        while next < COUNT {
+            if p.push(next).is_ok() {
+                next += 1;
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    });
+    let ct = std::thread::spawn(move || {
+        let mut expected = 0;
+        while expected < COUNT {
+            if let Ok(value) = c.pop() {
+                assert_eq!(value, expected);
+                expected += 1;
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    });
+    let mut snapshot = HistorySnapshot::with_capacity(8);
+    while snapshot.end_index() < COUNT {
+        reader.snapshot_into(&mut snapshot);
+        for (seq, item) in (snapshot.start_index()..).zip(snapshot.iter()) {
+            assert_eq!(seq, *item);
+        }
+        if let Ok(item) = reader.get(snapshot.end_index()) {
+            assert_eq!(item, snapshot.end_index());
+        }
+    }
+    pt.join().unwrap();
+    ct.join().unwrap();
+    reader.snapshot_into(&mut snapshot);
+    assert_eq!(snapshot.as_slice(), [COUNT - 2, COUNT - 1]);
+    assert_eq!(reader.get(COUNT - 3), Err(HistoryError::Expired));
+    assert_eq!(reader.get(COUNT), Err(HistoryError::NotYetWritten));
+}
+
+#[test]
+fn history_reader_pending_items() {
+    let (mut p, mut c, reader) = RingBuffer::with_history_reader(6, 2);
+    for i in 0..4 {
+        assert_eq!(p.push(i * 10), Ok(i));
+    }
+    assert_eq!(c.pop(), Ok(0));
+    assert_eq!(c.pop(), Ok(10));
+    let t = std::thread::spawn(move || {
+        assert_eq!(reader.get(2), Ok(20));
+        let snapshot = reader.snapshot();
+        match snapshot.start_index() {
+            0 => assert_eq!(snapshot.as_slice(), [0, 10, 20, 30]),
+            _ => assert_eq!(snapshot.as_slice(), [20, 30]),
+        }
+        reader
+    });
+    let chunk = c.read_chunk(2).unwrap();
+    assert_eq!(chunk.as_slices(), (&[20, 30][..], &[][..]));
+    chunk.commit_retaining(2);
+    let reader = t.join().unwrap();
+    assert_eq!(reader.snapshot().as_slice(), [20, 30]);
+
+    // Unread items can only be modified without a reader.
+    assert_eq!(p.push(40), Ok(4));
+    drop(reader);
+    let mut chunk = c.read_chunk(1).unwrap();
+    chunk.as_mut_slices().0[0] += 1;
+    chunk.commit_retaining(1);
+    assert_eq!(c.history().as_slices(), (&[30, 41][..], &[][..]));
+}
+
+#[test]
+#[should_panic(expected = "while a HistoryReader exists")]
+fn history_reader_prevents_modification() {
+    let (mut p, mut c, _reader) = RingBuffer::with_history_reader(4, 0);
+    assert_eq!(p.push(10), Ok(0));
+    let mut chunk = c.read_chunk(1).unwrap();
+    let _ = chunk.as_mut_slices();
+}
+
+#[test]
+fn history_window_from_the_back() {
+    let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    for i in 0..4 {
+        assert_eq!(p.push(i), Ok(i));
+        assert_eq!(c.pop(), Ok(i));
+    }
+    assert_eq!(p.push(4), Ok(4));
+    assert_eq!(p.push(5), Ok(5));
+
+    let history = c.history();
+    assert_eq!(history.start_index(), 2);
+    assert_eq!(history.as_slices(), (&[2, 3][..], &[4, 5][..]));
+
+    let mut iter = history.iter();
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.next_back(), Some(&5));
+    assert_eq!(iter.next(), Some(&2));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next_back(), Some(&4));
+    assert_eq!(iter.next_back(), Some(&3));
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next(), None);
+    assert_eq!(
+        history.iter().rev().copied().collect::<Vec<_>>(),
+        [5, 4, 3, 2]
+    );
+
+    let last = history.last(3);
+    assert_eq!(last.start_index(), 3);
+    assert_eq!(last.as_slices(), (&[3][..], &[4, 5][..]));
+    assert_eq!(history.last(0).as_slices(), (&[][..], &[][..]));
+    assert_eq!(history.last(9).len(), 4);
+
+    let range = history.range(3..5).unwrap();
+    assert_eq!(range.as_slices(), (&[3][..], &[4][..]));
+    assert_eq!(range.get(5), Err(HistoryError::NotYetWritten));
+    assert!(history.range(6..6).unwrap().is_empty());
+    assert_eq!(history.range(1..3).unwrap_err(), HistoryError::Expired);
+    assert_eq!(
+        history.range(4..7).unwrap_err(),
+        HistoryError::NotYetWritten
+    );
+}
+
+#[test]
+fn history_binary_search() {
+    let (mut p, mut c) = RingBuffer::with_replay(5, 3);
+    for i in 0..7 {
+        assert_eq!(p.push(i * 10), Ok(i));
+        assert_eq!(c.pop(), Ok(i * 10));
+    }
+    assert_eq!(p.push(70), Ok(7));
+    assert_eq!(p.push(70), Ok(8));
+
+    let history = c.history();
+    assert_eq!(history.start_index(), 4);
+    let (first, second) = history.as_slices();
+    assert!(!first.is_empty() && !second.is_empty());
+
+    for seq in 4..7 {
+        assert_eq!(history.binary_search_by(|x| x.cmp(&(seq * 10))), Ok(seq));
+    }
+    assert_eq!(history.binary_search_by(|x| x.cmp(&0)), Err(4));
+    assert_eq!(history.binary_search_by(|x| x.cmp(&55)), Err(6));
+    assert_eq!(history.binary_search_by(|x| x.cmp(&99)), Err(9));
+
+    assert_eq!(history.partition_point(|x| *x < 70), 7);
+    assert_eq!(history.partition_point(|x| *x <= 70), 9);
+    assert_eq!(history.partition_point(|_| false), 4);
+
+    let window = history.range_by_key(50..=70, |x| *x);
+    assert_eq!(window.start_index(), 5);
+    assert_eq!(window.iter().copied().collect::<Vec<_>>(), [50, 60, 70, 70]);
+    let window = history.range_by_key((Bound::Excluded(40), Bound::Excluded(70)), |x| *x);
+    assert_eq!(window.start_index(), 5);
+    assert_eq!(window.len(), 2);
+    assert!(history.range_by_key(..40, |x| *x).is_empty());
+    assert!(history
+        .range_by_key((Bound::Included(60), Bound::Excluded(50)), |x| *x)
+        .is_empty());
+    assert_eq!(history.range_by_key(.., |x| *x).len(), history.len());
+}
+
+#[test]
+fn transaction() {
+    let (mut p, mut c) = RingBuffer::with_replay(4, 1);
+    for i in 0..3 {
+        assert_eq!(p.push(i), Ok(i));
+    }
+
+    let mut tx = c.begin();
+    assert_eq!(tx.pop_with_seq(), Ok((0, 0)));
+    assert_eq!(tx.peek(), Ok(&1));
+    assert_eq!(tx.pop_with_seq(), Ok((1, 1)));
+    assert_eq!(tx.popped(), 2);
+    assert_eq!(tx.head(), 2);
+    tx.rollback();
+    // Nothing has been released to the producer.
+    assert_eq!(p.slots(), 0);
+    assert_eq!(c.head(), 0);
+    assert_eq!(c.retained(), 0);
+
+    let mut tx = c.begin();
+    assert_eq!(tx.pop(), Ok(0));
+    assert_eq!(tx.pop(), Ok(1));
+    tx.commit();
+    assert_eq!(c.head(), 2);
+    assert_eq!(c.retained(), 1);
+    assert_eq!(c.history().start_index(), 1);
+    assert_eq!(p.slots(), 2);
+
+    let mut tx = c.begin();
+    assert_eq!(tx.pop(), Ok(2));
+    assert_eq!(tx.pop(), Err(PopError::Empty));
+    assert_eq!(p.push(3), Ok(3));
+    assert_eq!(tx.pop(), Ok(3));
+    tx.commit();
+    assert_eq!(c.pop(), Err(PopError::Empty));
+    assert_eq!(c.rewind(1), Ok(()));
+    assert_eq!(c.pop_with_seq(), Ok((3, 3)));
+}
+
+#[test]
+fn subscribe_from() {
+    let (mut p, mut c) = RingBuffer::with_replay(6, 2);
+    for i in 0..4 {
+        assert_eq!(p.push(i), Ok(i));
+    }
+    assert_eq!(c.pop(), Ok(0));
+    assert_eq!(
+        c.subscribe_from(5).unwrap_err(),
+        HistoryError::NotYetWritten
+    );
+
+    // Skipping unread items retains them.
+    let mut subscription = c.subscribe_from(3).unwrap();
+    assert!(subscription.is_live());
+    assert_eq!(subscription.next(), Some((3, 3)));
+    assert_eq!(subscription.next(), None);
+    assert_eq!(c.retained(), 2);
+    assert_eq!(c.subscribe_from(1).unwrap_err(), HistoryError::Expired);
+    assert_eq!(c.subscribe_from(4).map(|s| s.head()), Ok(4));
+
+    let pt = std::thread::spawn(move || {
+        for i in 4..1000 {
+            while p.push(i).is_err() {
+                std::thread::yield_now();
+            }
+        }
+    });
+    let mut subscription = c.subscribe_from(2).unwrap();
+    assert!(!subscription.is_live());
+    let mut expected = 2;
+    while expected < 1000 {
+        if let Some((seq, item)) = subscription.next() {
+            assert_eq!(seq, expected);
+            assert_eq!(item, expected);
+            expected += 1;
+        } else {
+            std::thread::yield_now();
+        }
+    }
+    assert!(subscription.is_live());
+    pt.join().unwrap();
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "async")]
+
+use futures::executor::block_on;
+use futures::task::{noop_waker, Context, Poll};
+use futures::{SinkExt, StreamExt};
+use rtrb::{OverflowPolicy, PushError, RingBuffer};
+
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn stream_and_sink() {
+    const COUNT: u64 = 10_000;
+
+    let (mut p, c) = RingBuffer::new(4, 0);
+    let t = thread::spawn(move || {
+        block_on(async {
+            for i in 0..COUNT {
+                p.send(i).await.unwrap();
+            }
+        })
+    });
+    let items: Vec<_> = block_on(c.collect());
+    assert_eq!(items, (0..COUNT).collect::<Vec<_>>());
+    t.join().unwrap();
+}
+
+#[test]
+fn wake_on_resend_window() {
+    let (mut p, mut c) = RingBuffer::with_replay(3, 2);
+    block_on(async {
+        assert_eq!(p.send(10).await, Ok(()));
+        assert_eq!(c.next().await, Some(10));
+        assert_eq!(p.send(20).await, Ok(()));
+        assert_eq!(c.next().await, Some(20));
+        assert_eq!(p.send(30).await, Ok(()));
+    });
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        // This releases a retained item.
+        c.set_resend_window(1);
+        c
+    });
+    block_on(p.send(40)).unwrap();
+    let mut c = t.join().unwrap();
+    drop(p);
+    assert_eq!(block_on(c.by_ref().collect::<Vec<_>>()), [30, 40]);
+}
+
+#[test]
+fn sink_with_abandoned_consumer() {
+    let (mut p, c) = RingBuffer::new(1, 0);
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        drop(c);
+    });
+    block_on(async {
+        assert_eq!(p.send(10).await, Ok(()));
+        assert_eq!(p.send(20).await, Err(PushError::Full(20)));
+    });
+    t.join().unwrap();
+}
+
+#[test]
+fn sink_with_overflow_policy() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::OverwriteOldest);
+    block_on(p.send_all(&mut futures::stream::iter((0..5).map(Ok)))).unwrap();
+    assert_eq!(c.buffer().overflowed(), 3);
+    drop(p);
+    assert_eq!(block_on(c.by_ref().collect::<Vec<_>>()), [3, 4]);
+    assert_eq!(c.lost_total(), 3);
+
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::DropNewest);
+    block_on(p.send_all(&mut futures::stream::iter((0..5).map(Ok)))).unwrap();
+    assert_eq!(c.buffer().overflowed(), 3);
+    drop(p);
+    assert_eq!(block_on(c.by_ref().collect::<Vec<_>>()), [0, 1]);
+}
+
+#[test]
+fn poll_ready_does_not_overwrite() {
+    let (mut p, mut c) = RingBuffer::with_overflow_policy(2, 0, OverflowPolicy::OverwriteOldest);
+    assert_eq!(p.push(10), Ok(0));
+    assert_eq!(p.push(20), Ok(1));
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(p.poll_ready_unpin(&mut cx), Poll::Ready(Ok(())));
+    // The send future might be dropped without sending anything.
+    assert_eq!(c.buffer().overflowed(), 0);
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(p.poll_ready_unpin(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(p.start_send_unpin(30), Ok(()));
+    assert_eq!(p.poll_ready_unpin(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(p.start_send_unpin(40), Ok(()));
+    assert_eq!(c.buffer().overflowed(), 1);
+    drop(p);
+    assert_eq!(block_on(c.by_ref().collect::<Vec<_>>()), [30, 40]);
+    assert_eq!(c.lost_total(), 1);
+}
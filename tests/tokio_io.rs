@@ -0,0 +1,71 @@
+#![cfg(feature = "tokio")]
+
+use futures::executor::block_on;
+use rtrb::RingBuffer;
+use tokio_crate::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+
+use std::io::ErrorKind;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn copy_through_ring_buffer() {
+    let data: Vec<u8> = (0..10_000).map(|i| (i % 251) as u8).collect();
+    let expected = data.clone();
+
+    let (mut p, mut c) = RingBuffer::new(7, 0);
+    let t = thread::spawn(move || {
+        let copied = block_on(tokio_crate::io::copy(&mut &data[..], &mut p)).unwrap();
+        assert_eq!(copied, 10_000);
+    });
+    let mut received = Vec::new();
+    assert_eq!(block_on(c.read_to_end(&mut received)).unwrap(), 10_000);
+    assert_eq!(received, expected);
+    t.join().unwrap();
+}
+
+#[test]
+fn read_lines() {
+    let (mut p, mut c) = RingBuffer::new(8, 0);
+    let t = thread::spawn(move || {
+        block_on(async {
+            p.write_all(b"first\nsecond line\n").await.unwrap();
+            p.write_all(b"third").await.unwrap();
+        })
+    });
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    while block_on(c.read_line(&mut line)).unwrap() != 0 {
+        lines.push(line.clone());
+        line.clear();
+    }
+    assert_eq!(lines, ["first\n", "second line\n", "third"]);
+    t.join().unwrap();
+}
+
+#[test]
+fn fill_buf_wraps_around() {
+    let (mut p, mut c) = RingBuffer::new(4, 0);
+    block_on(async {
+        p.write_all(b"abc").await.unwrap();
+        assert_eq!(c.fill_buf().await.unwrap(), b"abc");
+        c.consume(2);
+        p.write_all(b"def").await.unwrap();
+        // The second part is only provided after the first one has been consumed.
+        assert_eq!(c.fill_buf().await.unwrap(), b"cd");
+        c.consume(2);
+        assert_eq!(c.fill_buf().await.unwrap(), b"ef");
+    });
+}
+
+#[test]
+fn write_with_abandoned_consumer() {
+    let (mut p, c) = RingBuffer::new(4, 0);
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        drop(c);
+    });
+    let error = block_on(p.write_all(b"too long")).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+    t.join().unwrap();
+}
@@ -1,6 +1,6 @@
 #![cfg(feature = "std")]
 
-use std::io::{Read, Write};
+use std::io::{BufRead, Read, Write};
 
 use rtrb::RingBuffer;
 
@@ -49,14 +49,14 @@ fn write_empty_buf() {
 #[test]
 fn read_empty_buf() {
     let (mut p, mut c) = RingBuffer::new(2, 0);
-    assert_eq!(p.push(99), Ok(()));
+    assert_eq!(p.push(99), Ok(0));
     assert_eq!(c.read(&mut []).unwrap(), 0);
 }
 
 #[test]
 fn write_error() {
     let (mut p, _c) = RingBuffer::new(1, 0);
-    assert_eq!(p.push(10), Ok(()));
+    assert_eq!(p.push(10), Ok(0));
     assert_eq!(
         p.write(&[99]).unwrap_err().kind(),
         std::io::ErrorKind::WouldBlock
@@ -72,3 +72,190 @@ fn read_error() {
         std::io::ErrorKind::WouldBlock
     );
 }
+
+#[test]
+fn write_respects_resend_window() {
+    let (mut p, mut c) = RingBuffer::with_replay(4, 2);
+    assert_eq!(p.write(&[10, 11, 12]).unwrap(), 2);
+    assert_eq!(
+        p.write(&[12]).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+    assert_eq!(c.pop(), Ok(10));
+    assert_eq!(p.write(&[12, 13]).unwrap(), 1);
+    c.rewind(1).unwrap();
+    let mut buf = [0; 3];
+    assert_eq!(c.read(&mut buf).unwrap(), 3);
+    assert_eq!(buf, [10, 11, 12]);
+}
+
+#[test]
+fn buf_read() {
+    let (mut p, mut c) = RingBuffer::new(8, 0);
+    assert_eq!(
+        c.fill_buf().unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+    p.write_all(b"one\ntwo").unwrap();
+    let mut line = String::new();
+    assert_eq!(c.read_line(&mut line).unwrap(), 4);
+    assert_eq!(line, "one\n");
+    p.write_all(b"\nthr").unwrap();
+    // The first slice ends where the ring buffer wraps around.
+    assert_eq!(c.fill_buf().unwrap(), b"two\n");
+    c.consume(2);
+    assert_eq!(c.fill_buf().unwrap(), b"o\n");
+    c.consume(2);
+    assert_eq!(c.fill_buf().unwrap(), b"thr");
+    c.consume(3);
+    assert_eq!(
+        c.fill_buf().unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+}
+
+#[test]
+fn buf_read_retains_consumed_bytes() {
+    let (mut p, mut c) = RingBuffer::new(8, 2);
+    p.write_all(b"abcdef").unwrap();
+    assert_eq!(c.fill_buf().unwrap(), b"abcdef");
+    c.consume(3);
+    assert_eq!(c.retained(), 2);
+    c.rewind(2).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(c.read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf, b"bcde");
+}
+
+#[test]
+fn seek() {
+    use std::io::{Seek, SeekFrom};
+
+    let (mut p, mut c) = RingBuffer::new(8, 3);
+    p.write_all(b"abcde").unwrap();
+    let mut buf = [0; 2];
+    c.read_exact(&mut buf).unwrap();
+    // Bytes read with Read are retained as well.
+    assert_eq!(c.seek(SeekFrom::Current(-1)).unwrap(), 1);
+    assert_eq!(c.seek(SeekFrom::Current(0)).unwrap(), 1);
+    assert_eq!(c.seek(SeekFrom::End(0)).unwrap(), 5);
+    assert_eq!(c.seek(SeekFrom::Start(2)).unwrap(), 2);
+    assert_eq!(
+        c.seek(SeekFrom::Start(1)).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        c.seek(SeekFrom::End(1)).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+    assert_eq!(c.seek(SeekFrom::Current(2)).unwrap(), 4);
+    c.read_exact(&mut buf[..1]).unwrap();
+    assert_eq!(&buf[..1], b"e");
+    p.write_all(b"fg").unwrap();
+    assert_eq!(c.seek(SeekFrom::End(-1)).unwrap(), 6);
+    assert_eq!(c.retained(), 3);
+    assert_eq!(c.seek(SeekFrom::Current(-1)).unwrap(), 5);
+    c.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"fg");
+}
+
+#[test]
+fn write_and_read_vectored() {
+    use std::io::{IoSlice, IoSliceMut};
+
+    let (mut p, mut c) = RingBuffer::new(5, 0);
+    assert_eq!(p.write(&[1, 2, 3]).unwrap(), 3);
+    assert_eq!(c.read(&mut [0; 3]).unwrap(), 3);
+    // The chunk wraps around, the buffers are split at different positions.
+    let bufs = [
+        IoSlice::new(&[10]),
+        IoSlice::new(&[]),
+        IoSlice::new(&[11, 12, 13]),
+        IoSlice::new(&[14]),
+    ];
+    assert_eq!(p.write_vectored(&bufs).unwrap(), 5);
+    assert_eq!(
+        p.write_vectored(&bufs).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+
+    let (mut a, mut b, mut d) = ([0; 1], [0; 3], [0; 2]);
+    let mut bufs = [
+        IoSliceMut::new(&mut a),
+        IoSliceMut::new(&mut b),
+        IoSliceMut::new(&mut d),
+    ];
+    assert_eq!(c.read_vectored(&mut bufs).unwrap(), 5);
+    assert_eq!(*bufs[0], [10]);
+    assert_eq!(*bufs[1], [11, 12, 13]);
+    // The last buffer is not completely filled.
+    assert_eq!(*bufs[2], [14, 0]);
+    assert_eq!(
+        c.read_vectored(&mut bufs).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+}
+
+#[test]
+fn fill_from_and_drain_into() {
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    let mut reader = &data[..];
+    let mut writer = Vec::new();
+
+    let (mut p, mut c) = RingBuffer::new(7, 2);
+    loop {
+        match p.fill_from(&mut reader) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::WouldBlock),
+        }
+        c.drain_into(&mut writer).unwrap();
+    }
+    assert_eq!(
+        c.drain_into(&mut writer).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+    assert_eq!(writer, data);
+    // The drained bytes are retained.
+    assert_eq!(c.retained(), 2);
+    c.rewind(2).unwrap();
+    let mut buf = [0; 2];
+    c.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, data[998..]);
+}
+
+#[test]
+fn fill_from_error() {
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::ConnectionReset.into())
+        }
+    }
+
+    let (mut p, c) = RingBuffer::new(4, 0);
+    assert_eq!(
+        p.fill_from(&mut Failing).unwrap_err().kind(),
+        std::io::ErrorKind::ConnectionReset
+    );
+    assert_eq!(c.slots(), 0);
+}
+
+#[test]
+fn overwrite_after_reading_from_empty_queue() {
+    let (mut p, mut c) =
+        RingBuffer::with_overflow_policy(2, 0, rtrb::OverflowPolicy::OverwriteOldest);
+    assert_eq!(
+        c.fill_buf().unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+    assert_eq!(
+        c.drain_into(&mut Vec::new()).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+    for i in 0..3 {
+        assert_eq!(p.push(i), Ok(u64::from(i)));
+    }
+    assert_eq!(c.lost_total(), 1);
+}